@@ -1,6 +1,7 @@
+use logistics_primitives::PackageId;
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GrandpaConfig, RuntimeGenesisConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, Balance, BalancesConfig, CarrierModuleConfig, GrandpaConfig,
+	PackageModuleConfig, RuntimeGenesisConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -124,6 +125,118 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 	))
 }
 
+/// Open packages in the demo marketplace as
+/// (client, pickup, destination, description, (length, width, height), weight, declared value)
+const DEMO_PACKAGES: &[(&str, &str, &str, &str, (u32, u32, u32), u32, Balance)] = &[
+	("Alice", "filled.count.soap", "index.home.raft", "Books", (30, 20, 15), 4, 12_000),
+	("Alice", "filled.count.soap", "crown.ample.tips", "Office chair", (70, 70, 110), 18, 45_000),
+	("Bob", "lofty.shape.dozen", "index.home.raft", "Frozen samples", (40, 30, 30), 9, 80_000),
+	("Bob", "lofty.shape.dozen", "spine.wisp.orbit", "Bicycle", (180, 25, 100), 14, 60_000),
+];
+
+/// Proposals in the demo marketplace as (carrier, index into `DEMO_PACKAGES`, maximum fee,
+/// minimum fee, penalty period, penalty amount)
+const DEMO_PROPOSALS: &[(&str, usize, u128, u128, u32, u128)] = &[
+	("Charlie", 0, 2_000, 1_200, 600, 100),
+	("Dave", 0, 1_800, 1_500, 1_200, 50),
+	("Eve", 1, 5_000, 3_000, 1_200, 250),
+	("Charlie", 2, 9_000, 6_000, 300, 500),
+	("Eve", 2, 7_500, 7_000, 600, 100),
+	("Dave", 3, 4_000, 2_500, 1_800, 150),
+];
+
+/// Package ID of the package at `index` in `DEMO_PACKAGES`
+fn demo_package_id(index: usize) -> PackageId {
+	let mut package_id = [0u8; 32];
+	package_id[24..].copy_from_slice(&(index as u64 + 1).to_be_bytes());
+	package_id
+}
+
+/// Local chain preloaded with a small logistics marketplace for QA.
+/// Alice and Bob are clients with open packages, Charlie, Dave and Eve are carriers
+/// who have made proposals on them.
+pub fn logistics_demo_config() -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"Logistics Demo",
+		// ID
+		"logistics_demo",
+		ChainType::Local,
+		move || {
+			let account = get_account_id_from_seed::<sr25519::Public>;
+
+			let packages = DEMO_PACKAGES
+				.iter()
+				.enumerate()
+				.map(|(index, package)| {
+					let (client, pickup, destination, description, dimensions, weight, value) =
+						package;
+					(
+						account(client),
+						demo_package_id(index),
+						pickup.as_bytes().to_vec(),
+						destination.as_bytes().to_vec(),
+						description.as_bytes().to_vec(),
+						*dimensions,
+						*weight,
+						*value,
+					)
+				})
+				.collect();
+
+			let proposals = DEMO_PROPOSALS
+				.iter()
+				.map(|(carrier, index, maximum_fee, minimum_fee, penalty_period, penalty)| {
+					(
+						account(carrier),
+						account(DEMO_PACKAGES[*index].0),
+						demo_package_id(*index),
+						*maximum_fee,
+						*minimum_fee,
+						*penalty_period,
+						*penalty,
+					)
+				})
+				.collect();
+
+			RuntimeGenesisConfig {
+				package_module: PackageModuleConfig { packages },
+				carrier_module: CarrierModuleConfig { proposals },
+				..testnet_genesis(
+					wasm_binary,
+					// Initial PoA authorities
+					vec![authority_keys_from_seed("Alice")],
+					// Sudo account
+					account("Alice"),
+					// Pre-funded accounts
+					vec![
+						account("Alice"),
+						account("Bob"),
+						account("Charlie"),
+						account("Dave"),
+						account("Eve"),
+						account("Ferdie"),
+					],
+					true,
+				)
+			}
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		// Properties
+		None,
+		None,
+		// Extensions
+		None,
+	))
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
@@ -154,5 +267,7 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		package_module: Default::default(),
+		carrier_module: Default::default(),
 	}
 }
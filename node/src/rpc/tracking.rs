@@ -0,0 +1,287 @@
+//! Package tracking subscription.
+//!
+//! `logistics_subscribePackage` follows the chain and notifies the subscriber of every
+//! block whose events touch a single package. When following best blocks, blocks which
+//! are retracted by a re-org are reported again as `retracted` notifications.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use frame_system::EventRecord;
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::{proc_macros::rpc, types::SubscriptionResult, SubscriptionSink};
+use logistics_primitives::PackageId;
+use node_template_runtime::{
+	opaque::Block, pallet_carrier, pallet_logistics, pallet_package, AccountId, Balance,
+	BlockNumber, Hash, Runtime, RuntimeEvent,
+};
+use pallet_logistics_rpc::LogisticsRuntimeApi;
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, twox_128, H256};
+
+/// Notification sent to package subscribers
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PackageUpdate {
+	/// A block contained events touching the package
+	#[serde(rename_all = "camelCase")]
+	Changed {
+		block_hash: Hash,
+		block_number: BlockNumber,
+		/// Events touching the package, as `Pallet.Event`
+		events: Vec<String>,
+		/// Status of the package after the block, `None` if it has been pruned
+		status: Option<String>,
+	},
+	/// A block previously reported as `changed` is no longer part of the best chain
+	#[serde(rename_all = "camelCase")]
+	Retracted { block_hash: Hash, block_number: BlockNumber },
+}
+
+#[rpc(server)]
+pub trait PackageTrackingApi {
+	/// Follow a package's progress.
+	///
+	/// Best blocks are followed by default. When `finalized` is set, only finalized
+	/// blocks are followed and no retractions will ever be sent.
+	#[subscription(
+		name = "logistics_subscribePackage" => "logistics_package",
+		unsubscribe = "logistics_unsubscribePackage",
+		item = PackageUpdate,
+	)]
+	fn subscribe_package(&self, client: AccountId, package_id: H256, finalized: Option<bool>);
+}
+
+/// Serves package tracking subscriptions.
+pub struct PackageTracking<C, B> {
+	client: Arc<C>,
+	executor: SubscriptionTaskExecutor,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> PackageTracking<C, B> {
+	pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+		Self { client, executor, _marker: Default::default() }
+	}
+}
+
+impl<C, B> PackageTrackingApiServer for PackageTracking<C, B>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block>,
+	C: StorageProvider<Block, B> + Send + Sync + 'static,
+	C::Api: LogisticsRuntimeApi<
+		Block,
+		AccountId,
+		BlockNumber,
+		Balance,
+		pallet_package::types::Package<Runtime>,
+		pallet_package::types::Manifest<Runtime>,
+		pallet_carrier::types::Proposal<Runtime>,
+	>,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	fn subscribe_package(
+		&self,
+		mut sink: SubscriptionSink,
+		client: AccountId,
+		package_id: H256,
+		finalized: Option<bool>,
+	) -> SubscriptionResult {
+		let tracker = Tracker::<C, B> {
+			client: self.client.clone(),
+			who: client,
+			package_id: package_id.0,
+			_marker: Default::default(),
+		};
+
+		let updates = if finalized.unwrap_or(false) {
+			self.client
+				.finality_notification_stream()
+				.flat_map(move |notification| {
+					// Finalizing a block implicitly finalizes every unfinalized ancestor
+					let updates = notification
+						.tree_route
+						.iter()
+						.chain(Some(&notification.hash))
+						.filter_map(|hash| tracker.changed(*hash))
+						.collect::<Vec<_>>();
+
+					stream::iter(updates)
+				})
+				.boxed()
+		} else {
+			self.client
+				.import_notification_stream()
+				.filter(|notification| future::ready(notification.is_new_best))
+				.flat_map(move |notification| {
+					let mut updates = Vec::new();
+
+					// On a re-org, retract whatever we reported on the old fork before
+					// replaying the blocks of the new one
+					if let Some(route) = &notification.tree_route {
+						updates.extend(route.retracted().iter().filter_map(|block| {
+							tracker.changed(block.hash).map(|_| PackageUpdate::Retracted {
+								block_hash: block.hash,
+								block_number: block.number,
+							})
+						}));
+						updates.extend(
+							route.enacted().iter().filter_map(|block| tracker.changed(block.hash)),
+						);
+					}
+					updates.extend(tracker.changed(notification.hash));
+
+					stream::iter(updates)
+				})
+				.boxed()
+		};
+
+		let fut = async move {
+			sink.pipe_from_stream(updates).await;
+		};
+		self.executor.spawn("logistics-rpc-subscription", Some("rpc"), fut.boxed());
+
+		Ok(())
+	}
+}
+
+/// Inspects blocks for events touching a single package
+struct Tracker<C, B> {
+	client: Arc<C>,
+	who: AccountId,
+	package_id: PackageId,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> Tracker<C, B>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, B>,
+	C::Api: LogisticsRuntimeApi<
+		Block,
+		AccountId,
+		BlockNumber,
+		Balance,
+		pallet_package::types::Package<Runtime>,
+		pallet_package::types::Manifest<Runtime>,
+		pallet_carrier::types::Proposal<Runtime>,
+	>,
+	B: Backend<Block>,
+{
+	/// `Changed` notification for a block, if any of its events touch the package
+	fn changed(&self, hash: Hash) -> Option<PackageUpdate> {
+		let events = self.events_at(hash);
+		if events.is_empty() {
+			return None
+		}
+
+		let block_number = self.client.number(hash).ok().flatten()?;
+		let status = self
+			.client
+			.runtime_api()
+			.package(hash, self.who.clone(), self.package_id)
+			.ok()
+			.flatten()
+			.map(|package| format!("{:?}", package.status));
+
+		Some(PackageUpdate::Changed { block_hash: hash, block_number, events, status })
+	}
+
+	/// Names of the events in a block which touch the package
+	fn events_at(&self, hash: Hash) -> Vec<String> {
+		let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+
+		let Ok(Some(data)) = self.client.storage(hash, &key) else { return Vec::new() };
+
+		// Blocks produced by a runtime with a different event layout are skipped
+		let Ok(records) = Vec::<EventRecord<RuntimeEvent, Hash>>::decode(&mut &data.0[..]) else {
+			return Vec::new()
+		};
+
+		records
+			.iter()
+			.filter_map(|record| package_event(&record.event, &self.who, &self.package_id))
+			.map(Into::into)
+			.collect()
+	}
+}
+
+/// Name of an event if it touches the given package
+fn package_event(
+	event: &RuntimeEvent,
+	who: &AccountId,
+	package_id: &PackageId,
+) -> Option<&'static str> {
+	use pallet_carrier::Event as Carrier;
+	use pallet_logistics::Event as Logistics;
+	use pallet_package::Event as Package;
+
+	let (client, package, name) = match event {
+		RuntimeEvent::PackageModule(event) => match event {
+			Package::PackageCreated { client, package } =>
+				(client, package, "PackageModule.PackageCreated"),
+			Package::PackageAssigned { client, package, .. } =>
+				(client, package, "PackageModule.PackageAssigned"),
+			Package::PackageCollected { client, package, .. } =>
+				(client, package, "PackageModule.PackageCollected"),
+			Package::PackageDelivered { client, package } =>
+				(client, package, "PackageModule.PackageDelivered"),
+			Package::PackageCancelled { client, package } =>
+				(client, package, "PackageModule.PackageCancelled"),
+			Package::PackageHeld { client, package, .. } =>
+				(client, package, "PackageModule.PackageHeld"),
+			Package::PackageReleased { client, package } =>
+				(client, package, "PackageModule.PackageReleased"),
+			Package::PackageDisputed { client, package } =>
+				(client, package, "PackageModule.PackageDisputed"),
+			Package::PackageReturned { client, package, .. } =>
+				(client, package, "PackageModule.PackageReturned"),
+			Package::PackageLost { client, package, .. } =>
+				(client, package, "PackageModule.PackageLost"),
+			Package::PackageExpired { client, package } =>
+				(client, package, "PackageModule.PackageExpired"),
+			Package::MetadataSet { client, package, .. } =>
+				(client, package, "PackageModule.MetadataSet"),
+			Package::MetadataCleared { client, package, .. } =>
+				(client, package, "PackageModule.MetadataCleared"),
+			Package::ExternalReferenceSet { client, package, .. } =>
+				(client, package, "PackageModule.ExternalReferenceSet"),
+			Package::LineItemsSet { client, package, .. } =>
+				(client, package, "PackageModule.LineItemsSet"),
+			Package::LineItemsReceived { client, package, .. } =>
+				(client, package, "PackageModule.LineItemsReceived"),
+			Package::PackageUpdated { client, package } =>
+				(client, package, "PackageModule.PackageUpdated"),
+			Package::__Ignore(..) => return None,
+		},
+		RuntimeEvent::CarrierModule(event) => match event {
+			Carrier::ProposalCreated { client, package, .. } =>
+				(client, package, "CarrierModule.ProposalCreated"),
+			Carrier::ProposalAccepted { client, package, .. } =>
+				(client, package, "CarrierModule.ProposalAccepted"),
+			Carrier::ProposalRejected { client, package, .. } =>
+				(client, package, "CarrierModule.ProposalRejected"),
+			Carrier::ProposalWithdrawn { client, package, .. } =>
+				(client, package, "CarrierModule.ProposalWithdrawn"),
+			Carrier::ProposalMarkedStale { client, package, .. } =>
+				(client, package, "CarrierModule.ProposalMarkedStale"),
+			Carrier::ProposalConfirmed { client, package, .. } =>
+				(client, package, "CarrierModule.ProposalConfirmed"),
+			Carrier::__Ignore(..) => return None,
+		},
+		RuntimeEvent::LogisticsModule(event) => match event {
+			Logistics::PackageDelivered { client, package, .. } =>
+				(client, package, "LogisticsModule.PackageDelivered"),
+			Logistics::ParameterSet { .. } |
+			Logistics::Paused { .. } |
+			Logistics::Unpaused { .. } |
+			Logistics::__Ignore(..) => return None,
+		},
+		_ => return None,
+	};
+
+	(client == who && package == package_id).then_some(name)
+}
@@ -3,9 +3,25 @@ use frame_support::pallet_macros::*;
 #[pallet_section]
 mod config {
 
+	use frame_support::traits::{Currency, ReservableCurrency};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The currency trait.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Deposit reserved from a carrier for each open proposal
+		#[pallet::constant]
+		type ProposalDeposit: Get<BalanceOf<Self>>;
+
+		/// Max number of proposals a single package can receive
+		#[pallet::constant]
+		type MaxProposalsPerPackage: Get<u32>;
 	}
 }
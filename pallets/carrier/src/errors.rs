@@ -10,5 +10,11 @@ mod errors {
 		ProposalDoesNotExist,
 		/// Proposal is not valid for requested action
 		InvalidProposal,
+		/// Minimum fee is greater than the maximum fee
+		InvalidFees,
+		/// Package has already received the maximum number of proposals
+		TooManyProposals,
+		/// Not enough funds available to reserve the proposal deposit
+		InsufficientFunds,
 	}
 }
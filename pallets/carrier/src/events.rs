@@ -10,12 +10,18 @@ mod events {
 			client: T::AccountId,
 			package: PackageId,
 			carrier: T::AccountId,
-			maximum_fee: u128,
-			minimum_fee: u128,
+			maximum_fee: BalanceOf<T>,
+			minimum_fee: BalanceOf<T>,
 		},
 		/// Proposal accepted [Client ID, Package ID, Carrier ID]
 		ProposalAccepted { client: T::AccountId, package: PackageId, carrier: T::AccountId },
 		/// Proposal rejected [Client ID, Package ID, Carrier ID]
 		ProposalRejected { client: T::AccountId, package: PackageId, carrier: T::AccountId },
+		/// Proposal withdrawn by its carrier [Client ID, Package ID, Carrier ID]
+		ProposalWithdrawn { client: T::AccountId, package: PackageId, carrier: T::AccountId },
+		/// Package changed after the proposal was made [Client ID, Package ID, Carrier ID]
+		ProposalMarkedStale { client: T::AccountId, package: PackageId, carrier: T::AccountId },
+		/// Carrier confirmed a stale proposal still stands [Client ID, Package ID, Carrier ID]
+		ProposalConfirmed { client: T::AccountId, package: PackageId, carrier: T::AccountId },
 	}
 }
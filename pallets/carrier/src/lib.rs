@@ -2,25 +2,39 @@
 
 pub use pallet::*;
 
-mod calls;
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 mod config;
 mod errors;
 mod events;
+pub mod migrations;
+pub mod traits;
 pub mod types;
 
-use crate::types::*;
-use frame_support::{pallet_macros::*, pallet_prelude::*, sp_runtime::SaturatedConversion};
+use crate::{traits::*, types::*};
+use frame_support::{
+	pallet_macros::*, pallet_prelude::*, sp_runtime::SaturatedConversion,
+	traits::ReservableCurrency,
+};
 use frame_system::pallet_prelude::*;
+use sp_std::vec::Vec;
 
 #[import_section(events::events)]
 #[import_section(errors::errors)]
 #[import_section(config::config)]
-#[import_section(calls::calls)]
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -34,10 +48,129 @@ pub mod pallet {
 		),
 		Proposal<T>,
 	>;
+
+	/// Number of proposals a package has received, bounded by `MaxProposalsPerPackage`
+	#[pallet::storage]
+	pub type ProposalCount<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		u32,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Open proposals as
+		/// (carrier, client, package id, maximum fee, minimum fee, penalty period, penalty
+		/// amount). Each carrier's proposal deposit is reserved.
+		pub proposals: Vec<(
+			T::AccountId,
+			T::AccountId,
+			PackageId,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			u32,
+			BalanceOf<T>,
+		)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (
+				carrier,
+				client,
+				package_id,
+				maximum_fee_amount,
+				minimum_fee_amount,
+				penalty_period,
+				penalty_amount,
+			) in &self.proposals
+			{
+				let terms = FeeTerms {
+					maximum_fee_amount: *maximum_fee_amount,
+					minimum_fee_amount: *minimum_fee_amount,
+					penalty_period: *penalty_period,
+					penalty_amount: *penalty_amount,
+				};
+
+				Pallet::<T>::create_proposal(carrier, client, package_id, terms)
+					.expect("genesis proposals are valid and their carriers can pay the deposit");
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(
+			_n: BlockNumberFor<T>,
+		) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
 }
 
 impl<T: Config> Pallet<T> {
-	pub fn proposal_is_valid(
+	/// Reject every open proposal on a package other than the carrier's,
+	/// returning their deposits. Bounded by `MaxProposalsPerPackage`.
+	fn reject_proposals(client: &T::AccountId, package_id: &PackageId, carrier: &T::AccountId) {
+		let open = Proposals::<T>::iter_prefix_values((&client, &package_id))
+			.filter(|proposal| proposal.carrier != *carrier && proposal.status.is_open())
+			.collect::<Vec<_>>();
+
+		for mut rejected_proposal in open {
+			rejected_proposal.status = ProposalStatus::Rejected;
+			T::Currency::unreserve(&rejected_proposal.carrier, rejected_proposal.deposit);
+			Proposals::<T>::insert(
+				(&client, &package_id, &rejected_proposal.carrier),
+				rejected_proposal.clone(),
+			);
+
+			Self::deposit_event(Event::<T>::ProposalRejected {
+				client: client.clone(),
+				package: package_id.clone(),
+				carrier: rejected_proposal.carrier,
+			});
+		}
+	}
+}
+
+impl<T: Config> ProposalInspect<T> for Pallet<T> {
+	fn proposal(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Option<Proposal<T>> {
+		Proposals::<T>::get((client, package_id, carrier))
+	}
+
+	fn proposals_for(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		offset: u32,
+		limit: u32,
+	) -> Vec<Proposal<T>> {
+		Proposals::<T>::iter_key_prefix((client, package_id))
+			.skip(offset as usize)
+			.take(limit as usize)
+			.filter_map(|carrier| Proposals::<T>::get((client, package_id, carrier)))
+			.collect()
+	}
+
+	fn proposals_by_carrier(carrier: &T::AccountId, offset: u32, limit: u32) -> Vec<Proposal<T>> {
+		Proposals::<T>::iter_values()
+			.filter(|proposal| proposal.carrier == *carrier)
+			.skip(offset as usize)
+			.take(limit as usize)
+			.collect()
+	}
+
+	fn proposal_is_valid(
 		client: &T::AccountId,
 		package_id: &PackageId,
 		carrier: &T::AccountId,
@@ -46,45 +179,98 @@ impl<T: Config> Pallet<T> {
 		proposal.is_some() && proposal.unwrap().status == ProposalStatus::Proposed
 	}
 
-	pub fn proposal_maximum_fee_amount(
+	fn proposal_maximum_fee_amount(
 		client: &T::AccountId,
 		package_id: &PackageId,
 		carrier: &T::AccountId,
-	) -> Option<u128> {
+	) -> Option<BalanceOf<T>> {
 		match Self::get_proposal((&client, &package_id, &carrier)) {
-			Some(proposal) => Some(proposal.maximum_fee_amount),
+			Some(proposal) => Some(proposal.terms.maximum_fee_amount),
 			None => None,
 		}
 	}
 
-	pub fn reject_proposals(client: &T::AccountId, package_id: &PackageId, carrier: &T::AccountId) {
-		for package_proposal in Proposals::<T>::iter_prefix((&client, &package_id)) {
-			let mut rejected_proposal = package_proposal.1;
-			if rejected_proposal.carrier != *carrier {
-				rejected_proposal.status = ProposalStatus::Rejected;
-				Proposals::<T>::insert(
-					(&client, &package_id, &rejected_proposal.carrier),
-					rejected_proposal.clone(),
-				);
-
-				Self::deposit_event(Event::<T>::ProposalRejected {
-					client: client.clone(),
-					package: package_id.clone(),
-					carrier: rejected_proposal.carrier,
-				});
-			}
-		}
+	fn proposal_minimum_fee_amount(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Option<BalanceOf<T>> {
+		Self::get_proposal((&client, &package_id, &carrier))
+			.map(|proposal| proposal.terms.minimum_fee_amount)
+	}
+
+	fn calculate_final_fee_amount(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+		collected_on: BlockNumberFor<T>,
+		delivered_on: BlockNumberFor<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let proposal = Proposals::<T>::get((&client, &package_id, &carrier))
+			.ok_or(Error::<T>::ProposalDoesNotExist)?;
+
+		// For every penalty period passed, deduct the penalty amount from the final fee
+		let blocks_taken = delivered_on.saturating_sub(collected_on);
+
+		Ok(proposal.terms.final_fee(blocks_taken.saturated_into()))
+	}
+}
+
+impl<T: Config> ProposalMutate<T> for Pallet<T> {
+	fn create_proposal(
+		carrier: &T::AccountId,
+		client: &T::AccountId,
+		package_id: &PackageId,
+		terms: FeeTerms<BalanceOf<T>>,
+	) -> DispatchResult {
+		// Can only submit 1 proposal at a time
+		ensure!(
+			!Proposals::<T>::contains_key((client, package_id, carrier)),
+			Error::<T>::ProposalExists
+		);
+
+		ensure!(terms.is_valid(), Error::<T>::InvalidFees);
+
+		// Keep the number of proposals a package can receive bounded
+		let proposals = ProposalCount::<T>::get(client, package_id);
+		ensure!(proposals < T::MaxProposalsPerPackage::get(), Error::<T>::TooManyProposals);
+
+		// Reserve proposal deposit
+		let deposit = T::ProposalDeposit::get();
+		T::Currency::reserve(carrier, deposit).map_err(|_| Error::<T>::InsufficientFunds)?;
+
+		// Insert new proposal into storage
+		Proposals::<T>::insert(
+			(client, package_id, carrier),
+			Proposal::new(*package_id, client.clone(), carrier.clone(), terms.clone(), deposit),
+		);
+		ProposalCount::<T>::insert(client, package_id, proposals + 1);
+
+		Self::deposit_event(Event::<T>::ProposalCreated {
+			client: client.clone(),
+			package: *package_id,
+			carrier: carrier.clone(),
+			maximum_fee: terms.maximum_fee_amount,
+			minimum_fee: terms.minimum_fee_amount,
+		});
+
+		Ok(())
 	}
 
-	pub fn accept_proposal(
+	fn accept_proposal(
 		client: &T::AccountId,
 		package_id: &PackageId,
 		carrier: &T::AccountId,
 	) -> DispatchResult {
 		let mut proposal = Proposals::<T>::get((&client, &package_id, &carrier))
 			.ok_or(Error::<T>::ProposalDoesNotExist)?;
+		ensure!(proposal.status == ProposalStatus::Proposed, Error::<T>::InvalidProposal);
+
+		Self::reject_proposals(client, package_id, carrier);
 
+		// The deposit has served its purpose once the proposal is accepted
 		proposal.status = ProposalStatus::Accepted;
+		T::Currency::unreserve(&carrier, proposal.deposit);
 		Proposals::<T>::insert((&client, &package_id, &carrier), proposal);
 
 		Self::deposit_event(Event::<T>::ProposalAccepted {
@@ -96,31 +282,134 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	pub fn calculate_final_fee_amount(
+	fn withdraw_proposal(
 		client: &T::AccountId,
 		package_id: &PackageId,
 		carrier: &T::AccountId,
-		collected_on: BlockNumberFor<T>,
-		delivered_on: BlockNumberFor<T>,
-	) -> Result<u128, frame_support::dispatch::DispatchError> {
-		let proposal = Proposals::<T>::get((&client, &package_id, &carrier))
+	) -> DispatchResult {
+		// Only proposals which are still open can be withdrawn
+		let proposal = Proposals::<T>::get((client, package_id, carrier))
 			.ok_or(Error::<T>::ProposalDoesNotExist)?;
+		ensure!(proposal.status.is_open(), Error::<T>::InvalidProposal);
 
-		// For every penalty period passed, deduct the penalty amount from the final fee
-		let blocks_taken = delivered_on - collected_on;
-		let periods = blocks_taken / proposal.penalty_period.into();
-		let penalty: u128 = periods.saturated_into::<u128>() * proposal.penalty_amount;
+		// Remove proposal and free up its slot on the package
+		Proposals::<T>::remove((client, package_id, carrier));
+		ProposalCount::<T>::mutate_exists(client, package_id, |proposals| {
+			*proposals = proposals.and_then(|count| count.checked_sub(1)).filter(|count| *count > 0)
+		});
+
+		// Return proposal deposit
+		T::Currency::unreserve(carrier, proposal.deposit);
 
-		let final_fee = if proposal.maximum_fee_amount - penalty > proposal.minimum_fee_amount {
-			proposal.maximum_fee_amount - penalty
-		} else {
-			proposal.minimum_fee_amount
-		};
+		Self::deposit_event(Event::<T>::ProposalWithdrawn {
+			client: client.clone(),
+			package: *package_id,
+			carrier: carrier.clone(),
+		});
 
-		Ok(final_fee)
+		Ok(())
 	}
 
-	pub fn remove_concluded_proposals(prefix: (T::AccountId, PackageId)) {
-		let _ = Proposals::<T>::clear_prefix(prefix, u32::MAX, None);
+	fn mark_proposals_stale(client: &T::AccountId, package_id: &PackageId) -> u32 {
+		let open = Proposals::<T>::iter_prefix_values((client, package_id))
+			.filter(|proposal| proposal.status == ProposalStatus::Proposed)
+			.collect::<Vec<_>>();
+		let stale = open.len() as u32;
+
+		for mut proposal in open {
+			proposal.status = ProposalStatus::Stale;
+			Proposals::<T>::insert((client, package_id, &proposal.carrier), proposal.clone());
+
+			Self::deposit_event(Event::<T>::ProposalMarkedStale {
+				client: client.clone(),
+				package: *package_id,
+				carrier: proposal.carrier,
+			});
+		}
+
+		stale
+	}
+
+	fn confirm_proposal(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult {
+		Proposals::<T>::try_mutate((client, package_id, carrier), |proposal| {
+			let proposal = proposal.as_mut().ok_or(Error::<T>::ProposalDoesNotExist)?;
+			ensure!(proposal.status == ProposalStatus::Stale, Error::<T>::InvalidProposal);
+
+			proposal.status = ProposalStatus::Proposed;
+
+			Ok::<_, Error<T>>(())
+		})?;
+
+		Self::deposit_event(Event::<T>::ProposalConfirmed {
+			client: client.clone(),
+			package: *package_id,
+			carrier: carrier.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn remove_concluded_proposals(prefix: (T::AccountId, PackageId)) -> u32 {
+		let (client, package_id) = prefix;
+		let mut removed = 0;
+
+		for proposal in Proposals::<T>::drain_prefix((&client, &package_id)).map(|(_, p)| p) {
+			if proposal.status.is_open() {
+				T::Currency::unreserve(&proposal.carrier, proposal.deposit);
+			}
+			removed += 1;
+		}
+		ProposalCount::<T>::remove(&client, &package_id);
+
+		removed
+	}
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config> Pallet<T> {
+	/// Check that proposal counts are accurate and no package has more than one accepted proposal
+	pub fn do_try_state() -> Result<(), DispatchError> {
+		use sp_std::collections::btree_map::BTreeMap;
+
+		// Number of proposals and accepted proposals on each package
+		let mut packages = BTreeMap::<(T::AccountId, PackageId), (u32, u32)>::new();
+
+		for ((client, package_id, carrier), proposal) in Proposals::<T>::iter() {
+			ensure!(
+				proposal.client == client &&
+					proposal.package == package_id &&
+					proposal.carrier == carrier,
+				"Proposal is stored under another proposal's key"
+			);
+
+			let (proposals, accepted) = packages.entry((client, package_id)).or_default();
+			*proposals += 1;
+			if proposal.status == ProposalStatus::Accepted {
+				*accepted += 1;
+			}
+		}
+
+		for ((client, package_id), (proposals, accepted)) in packages.iter() {
+			ensure!(*accepted <= 1, "Package has more than one accepted proposal");
+			ensure!(
+				ProposalCount::<T>::get(client, package_id) == *proposals,
+				"Proposal count does not match the package's proposals"
+			);
+			ensure!(
+				*proposals <= T::MaxProposalsPerPackage::get(),
+				"Package has more proposals than allowed"
+			);
+		}
+
+		ensure!(
+			ProposalCount::<T>::iter_keys().all(|key| packages.contains_key(&key)),
+			"Proposal count is kept for a package without proposals"
+		);
+
+		Ok(())
 	}
 }
@@ -0,0 +1,104 @@
+//! Storage migrations for the carrier pallet.
+//!
+//! Each migration checks the on-chain storage version before touching any state,
+//! so it is safe to leave in the runtime's `Migrations` after it has run.
+
+use super::*;
+use codec::{Decode, Encode};
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::TryRuntimeError;
+use frame_support::{
+	sp_runtime::traits::Zero,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData};
+
+/// Version 1 adds a deposit to every proposal and counts the proposals on each package
+pub mod v1 {
+	use super::*;
+
+	/// Proposal as stored before version 1
+	#[derive(Encode, Decode)]
+	pub struct OldProposal<T: Config> {
+		pub package: PackageId,
+		pub client: T::AccountId,
+		pub carrier: T::AccountId,
+		pub maximum_fee_amount: u128,
+		pub minimum_fee_amount: u128,
+		pub penalty_period: u32,
+		pub penalty_amount: u128,
+		pub proposed_on: BlockNumberFor<T>,
+		pub status: ProposalStatus,
+	}
+
+	/// Proposals made before deposits were introduced never reserved one,
+	/// so they are given a deposit of zero
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut counts = BTreeMap::<(T::AccountId, PackageId), u32>::new();
+			let mut translated = 0u64;
+
+			Proposals::<T>::translate::<OldProposal<T>, _>(|(client, package_id, _), old| {
+				translated += 1;
+				*counts.entry((client, package_id)).or_default() += 1;
+
+				Some(Proposal {
+					package: old.package,
+					client: old.client,
+					carrier: old.carrier,
+					terms: FeeTerms {
+						maximum_fee_amount: old.maximum_fee_amount.saturated_into(),
+						minimum_fee_amount: old.minimum_fee_amount.saturated_into(),
+						penalty_period: old.penalty_period,
+						penalty_amount: old.penalty_amount.saturated_into(),
+					},
+					proposed_on: old.proposed_on,
+					status: old.status,
+					deposit: Zero::zero(),
+				})
+			});
+
+			let packages = counts.len() as u64;
+			for ((client, package_id), count) in counts {
+				ProposalCount::<T>::insert(client, package_id, count);
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + packages + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((Proposals::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let proposals =
+				u32::decode(&mut &state[..]).map_err(|_| "Pre-upgrade state is invalid")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"Storage version was not updated"
+			);
+			// Values which fail to decode are skipped, so any corrupt proposal changes the count
+			ensure!(
+				Proposals::<T>::iter_values().count() as u32 == proposals,
+				"Proposals do not decode after the migration"
+			);
+			ensure!(
+				ProposalCount::<T>::iter_values().sum::<u32>() == proposals,
+				"Proposal counts do not add up to the number of proposals"
+			);
+
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,443 @@
+use crate::{migrations::v1, mock::*, traits::*, types::*, Error, Event, ProposalCount, Proposals};
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::unhashed,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_runtime::BuildStorage;
+
+const PACKAGE_ID: PackageId = [1u8; 32];
+
+fn terms(maximum: u64, minimum: u64, penalty_period: u32, penalty: u64) -> FeeTerms<u64> {
+	FeeTerms {
+		maximum_fee_amount: maximum,
+		minimum_fee_amount: minimum,
+		penalty_period,
+		penalty_amount: penalty,
+	}
+}
+
+fn propose(carrier: u64) {
+	assert_ok!(CarrierModule::create_proposal(
+		&carrier,
+		&CLIENT,
+		&PACKAGE_ID,
+		terms(100, 40, 10, 15)
+	));
+}
+
+fn status(carrier: u64) -> Option<ProposalStatus> {
+	Proposals::<Test>::get((CLIENT, PACKAGE_ID, carrier)).map(|proposal| proposal.status)
+}
+
+#[test]
+fn create_proposal_reserves_deposit() {
+	build_and_execute(|| {
+		propose(CARRIER);
+
+		let proposal = Proposals::<Test>::get((CLIENT, PACKAGE_ID, CARRIER)).unwrap();
+		assert_eq!(proposal.status, ProposalStatus::Proposed);
+		assert_eq!(proposal.deposit, 5);
+		assert_eq!(proposal.proposed_on, 1);
+		assert_eq!(Balances::reserved_balance(CARRIER), 5);
+		assert_eq!(ProposalCount::<Test>::get(CLIENT, PACKAGE_ID), 1);
+
+		System::assert_last_event(
+			Event::ProposalCreated {
+				client: CLIENT,
+				package: PACKAGE_ID,
+				carrier: CARRIER,
+				maximum_fee: 100,
+				minimum_fee: 40,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn create_proposal_rejects_duplicate_proposals() {
+	build_and_execute(|| {
+		propose(CARRIER);
+
+		assert_noop!(
+			CarrierModule::create_proposal(&CARRIER, &CLIENT, &PACKAGE_ID, terms(90, 40, 10, 15)),
+			Error::<Test>::ProposalExists
+		);
+	});
+}
+
+#[test]
+fn create_proposal_rejects_minimum_fee_above_maximum() {
+	build_and_execute(|| {
+		assert_noop!(
+			CarrierModule::create_proposal(&CARRIER, &CLIENT, &PACKAGE_ID, terms(40, 41, 10, 15)),
+			Error::<Test>::InvalidFees
+		);
+	});
+}
+
+#[test]
+fn create_proposal_requires_deposit() {
+	build_and_execute(|| {
+		assert_noop!(
+			CarrierModule::create_proposal(
+				&POOR_CARRIER,
+				&CLIENT,
+				&PACKAGE_ID,
+				terms(100, 40, 10, 15)
+			),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn proposals_per_package_are_bounded() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		propose(THIRD_CARRIER);
+
+		assert_noop!(
+			CarrierModule::create_proposal(
+				&FOURTH_CARRIER,
+				&CLIENT,
+				&PACKAGE_ID,
+				terms(100, 40, 10, 15)
+			),
+			Error::<Test>::TooManyProposals
+		);
+
+		// Other packages are unaffected
+		assert_ok!(CarrierModule::create_proposal(
+			&FOURTH_CARRIER,
+			&CLIENT,
+			&[2u8; 32],
+			terms(100, 40, 10, 15)
+		));
+	});
+}
+
+#[test]
+fn accept_proposal_rejects_every_other_proposal() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		propose(THIRD_CARRIER);
+
+		assert_ok!(CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+
+		assert_eq!(status(CARRIER), Some(ProposalStatus::Accepted));
+		assert_eq!(status(OTHER_CARRIER), Some(ProposalStatus::Rejected));
+		assert_eq!(status(THIRD_CARRIER), Some(ProposalStatus::Rejected));
+
+		// Every deposit is returned once the package has a carrier
+		for carrier in [CARRIER, OTHER_CARRIER, THIRD_CARRIER] {
+			assert_eq!(Balances::reserved_balance(carrier), 0);
+			assert_eq!(Balances::free_balance(carrier), 1_000);
+		}
+
+		for carrier in [OTHER_CARRIER, THIRD_CARRIER] {
+			System::assert_has_event(
+				Event::ProposalRejected { client: CLIENT, package: PACKAGE_ID, carrier }.into(),
+			);
+		}
+		System::assert_last_event(
+			Event::ProposalAccepted { client: CLIENT, package: PACKAGE_ID, carrier: CARRIER }
+				.into(),
+		);
+	});
+}
+
+#[test]
+fn accept_proposal_requires_open_proposal() {
+	build_and_execute(|| {
+		assert_noop!(
+			CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::ProposalDoesNotExist
+		);
+
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		assert_ok!(CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+
+		// Neither the accepted nor a rejected proposal can be accepted again
+		assert_noop!(
+			CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::InvalidProposal
+		);
+		assert_noop!(
+			CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &OTHER_CARRIER),
+			Error::<Test>::InvalidProposal
+		);
+	});
+}
+
+#[test]
+fn withdraw_proposal_returns_deposit_and_frees_slot() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		propose(THIRD_CARRIER);
+
+		assert_ok!(CarrierModule::withdraw_proposal(&CLIENT, &PACKAGE_ID, &OTHER_CARRIER));
+
+		assert_eq!(status(OTHER_CARRIER), None);
+		assert_eq!(Balances::reserved_balance(OTHER_CARRIER), 0);
+		assert_eq!(ProposalCount::<Test>::get(CLIENT, PACKAGE_ID), 2);
+		System::assert_last_event(
+			Event::ProposalWithdrawn {
+				client: CLIENT,
+				package: PACKAGE_ID,
+				carrier: OTHER_CARRIER,
+			}
+			.into(),
+		);
+
+		// The freed slot can be taken by another carrier
+		propose(FOURTH_CARRIER);
+	});
+}
+
+#[test]
+fn withdraw_proposal_removes_count_of_last_proposal() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		assert_ok!(CarrierModule::withdraw_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+
+		assert!(!ProposalCount::<Test>::contains_key(CLIENT, PACKAGE_ID));
+	});
+}
+
+#[test]
+fn withdraw_proposal_requires_open_proposal() {
+	build_and_execute(|| {
+		assert_noop!(
+			CarrierModule::withdraw_proposal(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::ProposalDoesNotExist
+		);
+
+		propose(CARRIER);
+		assert_ok!(CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+
+		assert_noop!(
+			CarrierModule::withdraw_proposal(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::InvalidProposal
+		);
+	});
+}
+
+#[test]
+fn stale_proposals_must_be_confirmed_before_acceptance() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		assert_ok!(CarrierModule::withdraw_proposal(&CLIENT, &PACKAGE_ID, &OTHER_CARRIER));
+		propose(THIRD_CARRIER);
+
+		assert_eq!(CarrierModule::mark_proposals_stale(&CLIENT, &PACKAGE_ID), 2);
+		assert_eq!(status(CARRIER), Some(ProposalStatus::Stale));
+		assert!(!CarrierModule::proposal_is_valid(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_noop!(
+			CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::InvalidProposal
+		);
+
+		// Stale proposals keep their deposits
+		assert_eq!(Balances::reserved_balance(CARRIER), 5);
+		System::assert_has_event(
+			Event::ProposalMarkedStale { client: CLIENT, package: PACKAGE_ID, carrier: CARRIER }
+				.into(),
+		);
+
+		assert_ok!(CarrierModule::confirm_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_eq!(status(CARRIER), Some(ProposalStatus::Proposed));
+		System::assert_last_event(
+			Event::ProposalConfirmed { client: CLIENT, package: PACKAGE_ID, carrier: CARRIER }
+				.into(),
+		);
+		assert_noop!(
+			CarrierModule::confirm_proposal(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::InvalidProposal
+		);
+
+		// Accepting the confirmed proposal still rejects the stale one
+		assert_ok!(CarrierModule::accept_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_eq!(status(THIRD_CARRIER), Some(ProposalStatus::Rejected));
+		assert_eq!(Balances::reserved_balance(THIRD_CARRIER), 0);
+	});
+}
+
+#[test]
+fn stale_proposals_can_be_withdrawn() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		CarrierModule::mark_proposals_stale(&CLIENT, &PACKAGE_ID);
+
+		assert_ok!(CarrierModule::withdraw_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_eq!(status(CARRIER), None);
+		assert_eq!(Balances::reserved_balance(CARRIER), 0);
+	});
+}
+
+#[test]
+fn final_fee_deducts_a_penalty_for_every_full_period() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		let fee = |delivered_on| {
+			CarrierModule::calculate_final_fee_amount(
+				&CLIENT,
+				&PACKAGE_ID,
+				&CARRIER,
+				10,
+				delivered_on,
+			)
+			.unwrap()
+		};
+
+		// Maximum fee 100, penalty of 15 every 10 blocks, minimum fee 40
+		assert_eq!(fee(10), 100);
+		assert_eq!(fee(19), 100);
+		assert_eq!(fee(20), 85);
+		assert_eq!(fee(39), 85);
+		assert_eq!(fee(40), 70);
+		assert_eq!(fee(50), 55);
+		assert_eq!(fee(60), 40);
+		assert_eq!(fee(70), 40);
+	});
+}
+
+#[test]
+fn final_fee_edge_cases_do_not_panic() {
+	build_and_execute(|| {
+		// No penalty period
+		assert_ok!(CarrierModule::create_proposal(
+			&CARRIER,
+			&CLIENT,
+			&PACKAGE_ID,
+			terms(100, 40, 0, 15)
+		));
+		// Penalty larger than the maximum fee
+		assert_ok!(CarrierModule::create_proposal(
+			&OTHER_CARRIER,
+			&CLIENT,
+			&PACKAGE_ID,
+			terms(100, 0, 1, u64::MAX)
+		));
+
+		assert_eq!(
+			CarrierModule::calculate_final_fee_amount(&CLIENT, &PACKAGE_ID, &CARRIER, 1, 1_000),
+			Ok(100)
+		);
+		assert_eq!(
+			CarrierModule::calculate_final_fee_amount(
+				&CLIENT,
+				&PACKAGE_ID,
+				&OTHER_CARRIER,
+				1,
+				1_000
+			),
+			Ok(0)
+		);
+		// Delivery recorded before collection
+		assert_eq!(
+			CarrierModule::calculate_final_fee_amount(&CLIENT, &PACKAGE_ID, &OTHER_CARRIER, 5, 1),
+			Ok(100)
+		);
+		assert_eq!(
+			CarrierModule::calculate_final_fee_amount(&CLIENT, &PACKAGE_ID, &THIRD_CARRIER, 1, 5),
+			Err(Error::<Test>::ProposalDoesNotExist.into())
+		);
+	});
+}
+
+#[test]
+fn removing_concluded_proposals_returns_open_deposits() {
+	build_and_execute(|| {
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		CarrierModule::mark_proposals_stale(&CLIENT, &PACKAGE_ID);
+		propose(THIRD_CARRIER);
+
+		assert_eq!(CarrierModule::remove_concluded_proposals((CLIENT, PACKAGE_ID)), 3);
+
+		assert_eq!(Proposals::<Test>::iter_prefix((CLIENT, PACKAGE_ID)).count(), 0);
+		assert!(!ProposalCount::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert_eq!(Balances::reserved_balance(CARRIER), 0);
+		assert_eq!(Balances::reserved_balance(OTHER_CARRIER), 0);
+		assert_eq!(Balances::reserved_balance(THIRD_CARRIER), 0);
+	});
+}
+
+#[test]
+fn migration_to_v1_adds_deposits_and_counts_proposals() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<CarrierModule>();
+		for (package_id, carrier) in
+			[(PACKAGE_ID, CARRIER), (PACKAGE_ID, OTHER_CARRIER), ([2u8; 32], CARRIER)]
+		{
+			let old = v1::OldProposal::<Test> {
+				package: package_id,
+				client: CLIENT,
+				carrier,
+				maximum_fee_amount: 100,
+				minimum_fee_amount: 40,
+				penalty_period: 10,
+				penalty_amount: 15,
+				proposed_on: 1,
+				status: ProposalStatus::Proposed,
+			};
+			unhashed::put(&Proposals::<Test>::hashed_key_for((CLIENT, package_id, carrier)), &old);
+		}
+
+		#[cfg(feature = "try-runtime")]
+		let state = v1::MigrateToV1::<Test>::pre_upgrade().unwrap();
+		v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(v1::MigrateToV1::<Test>::post_upgrade(state));
+
+		let proposal = Proposals::<Test>::get((CLIENT, PACKAGE_ID, OTHER_CARRIER)).unwrap();
+		assert_eq!(proposal.terms, terms(100, 40, 10, 15));
+		assert_eq!(proposal.status, ProposalStatus::Proposed);
+		assert_eq!(proposal.deposit, 0);
+		assert_eq!(ProposalCount::<Test>::get(CLIENT, PACKAGE_ID), 2);
+		assert_eq!(ProposalCount::<Test>::get(CLIENT, [2u8; 32]), 1);
+		assert_eq!(CarrierModule::on_chain_storage_version(), 1);
+
+		// Migrated proposals can be withdrawn without touching the carrier's balance
+		assert_ok!(CarrierModule::withdraw_proposal(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_eq!(Balances::free_balance(CARRIER), 1_000);
+		assert_ok!(CarrierModule::do_try_state());
+	});
+}
+
+#[test]
+fn genesis_proposals_are_open_and_reserve_deposits() {
+	let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig {
+		balances: pallet_balances::GenesisConfig {
+			balances: vec![(CARRIER, 1_000), (OTHER_CARRIER, 1_000)],
+		},
+		carrier_module: crate::GenesisConfig {
+			proposals: vec![
+				(CARRIER, CLIENT, PACKAGE_ID, 100, 40, 10, 15),
+				(OTHER_CARRIER, CLIENT, PACKAGE_ID, 90, 30, 10, 5),
+			],
+		},
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+
+	ext.execute_with(|| {
+		assert_eq!(status(CARRIER), Some(ProposalStatus::Proposed));
+		assert_eq!(status(OTHER_CARRIER), Some(ProposalStatus::Proposed));
+		assert_eq!(ProposalCount::<Test>::get(CLIENT, PACKAGE_ID), 2);
+		assert_eq!(Balances::reserved_balance(CARRIER), 5);
+		assert_eq!(Balances::reserved_balance(OTHER_CARRIER), 5);
+
+		assert_eq!(CarrierModule::on_chain_storage_version(), 1);
+		assert_ok!(CarrierModule::do_try_state());
+	});
+}
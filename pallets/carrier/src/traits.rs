@@ -0,0 +1,101 @@
+//! Interfaces through which other pallets work with carrier proposals.
+//!
+//! This pallet has no calls of its own. Proposals are only changed through [`ProposalMutate`],
+//! by a pallet which reserves the client's fee when a proposal is accepted.
+
+use crate::{types::*, BalanceOf, Config};
+use frame_support::dispatch::{DispatchError, DispatchResult};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_std::vec::Vec;
+
+/// Read access to proposals and the fees they set
+pub trait ProposalInspect<T: Config> {
+	/// Proposal a carrier made on a client's package
+	fn proposal(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Option<Proposal<T>>;
+
+	/// Proposals made on a package whatever their status,
+	/// skipping the first `offset` and returning at most `limit`
+	fn proposals_for(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		offset: u32,
+		limit: u32,
+	) -> Vec<Proposal<T>>;
+
+	/// Proposals a carrier has made across all clients, paged like `proposals_for`.
+	/// Proposals are not indexed by carrier, so this is only suitable for use off-chain.
+	fn proposals_by_carrier(carrier: &T::AccountId, offset: u32, limit: u32) -> Vec<Proposal<T>>;
+
+	/// Proposal exists and is still open to be accepted
+	fn proposal_is_valid(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> bool;
+
+	fn proposal_maximum_fee_amount(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Option<BalanceOf<T>>;
+
+	fn proposal_minimum_fee_amount(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Option<BalanceOf<T>>;
+
+	/// Fee owed to the carrier for a delivery, after any penalties
+	fn calculate_final_fee_amount(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+		collected_on: BlockNumberFor<T>,
+		delivered_on: BlockNumberFor<T>,
+	) -> Result<BalanceOf<T>, DispatchError>;
+}
+
+/// Changes to proposals. Proposal deposits are handled here, fees are up to the caller.
+pub trait ProposalMutate<T: Config>: ProposalInspect<T> {
+	/// Propose to carry a client's package, reserving the proposal deposit from the carrier
+	fn create_proposal(
+		carrier: &T::AccountId,
+		client: &T::AccountId,
+		package_id: &PackageId,
+		terms: FeeTerms<BalanceOf<T>>,
+	) -> DispatchResult;
+
+	/// Accept an open proposal and reject every other open proposal on the package.
+	/// Bounded by `MaxProposalsPerPackage`.
+	fn accept_proposal(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Withdraw an open proposal on behalf of its carrier, returning the deposit
+	fn withdraw_proposal(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Mark every open proposal on a package as stale after the package has been amended.
+	/// Returns how many were marked. Bounded by `MaxProposalsPerPackage`.
+	fn mark_proposals_stale(client: &T::AccountId, package_id: &PackageId) -> u32;
+
+	/// Confirm a stale proposal on behalf of its carrier, so that it can be accepted again
+	fn confirm_proposal(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Remove every proposal for a concluded package, returning how many were removed.
+	/// Deposits of proposals which were never accepted or rejected are returned.
+	fn remove_concluded_proposals(prefix: (T::AccountId, PackageId)) -> u32;
+}
@@ -1,17 +1,10 @@
+use crate::{BalanceOf, Config};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::sp_runtime::RuntimeDebug;
-use frame_system::{self as system, pallet_prelude::BlockNumberFor, Config};
+use frame_system::{self as system, pallet_prelude::BlockNumberFor};
 use scale_info::TypeInfo;
 
-pub type PackageId = [u8; 32];
-
-#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
-pub enum ProposalStatus {
-	Proposed,
-	Accepted,
-	Rejected,
-	Cancelled,
-}
+pub use logistics_primitives::{FeeTerms, PackageId, ProposalStatus};
 
 #[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
 #[scale_info(skip_type_params(T))]
@@ -19,12 +12,12 @@ pub struct Proposal<T: Config> {
 	pub package: PackageId,
 	pub client: T::AccountId,
 	pub carrier: T::AccountId,
-	pub maximum_fee_amount: u128,
-	pub minimum_fee_amount: u128,
-	pub penalty_period: u32,
-	pub penalty_amount: u128,
+	/// Laid out as the separate fee fields were before, so stored proposals decode unchanged
+	pub terms: FeeTerms<BalanceOf<T>>,
 	pub proposed_on: BlockNumberFor<T>,
 	pub status: ProposalStatus,
+	/// Deposit reserved from the carrier while the proposal is open
+	pub deposit: BalanceOf<T>,
 }
 
 impl<T: Config> Proposal<T> {
@@ -32,21 +25,17 @@ impl<T: Config> Proposal<T> {
 		package: PackageId,
 		client: T::AccountId,
 		carrier: T::AccountId,
-		maximum_fee_amount: u128,
-		minimum_fee_amount: u128,
-		penalty_period: u32,
-		penalty_amount: u128,
+		terms: FeeTerms<BalanceOf<T>>,
+		deposit: BalanceOf<T>,
 	) -> Self {
 		Proposal {
 			package,
 			client,
 			carrier,
-			maximum_fee_amount,
-			minimum_fee_amount,
-			penalty_period,
-			penalty_amount,
+			terms,
 			proposed_on: <system::Pallet<T>>::block_number(),
 			status: ProposalStatus::Proposed,
+			deposit,
 		}
 	}
 }
@@ -0,0 +1,143 @@
+//! Benchmarking setup for pallet-insurance
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as InsuranceModule;
+use frame_benchmarking::v2::*;
+use frame_support::sp_runtime::traits::Bounded;
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+const PACKAGE_ID: PackageId = [1u8; 32];
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let account: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&account, BalanceOf::<T>::max_value() / 4u32.into());
+	account
+}
+
+/// Deposit into the pool from an underwriter, returning the underwriter
+fn deposit<T: Config>(index: u32) -> T::AccountId {
+	let underwriter = funded_account::<T>("underwriter", index);
+	InsuranceModule::<T>::pool_deposit(
+		RawOrigin::Signed(underwriter.clone()).into(),
+		1_000_000u32.into(),
+	)
+	.expect("underwriter is funded; qed");
+	underwriter
+}
+
+/// Fund the pool and insure a package, returning the client
+fn insured_package<T: Config>() -> T::AccountId {
+	deposit::<T>(0);
+
+	let client = funded_account::<T>("client", 0);
+	InsuranceModule::<T>::purchase_cover(
+		&client,
+		&PACKAGE_ID,
+		&account("carrier", 0, SEED),
+		1_000u32.into(),
+		true,
+		true,
+	)
+	.expect("pool has capacity; qed");
+	client
+}
+
+/// Insure a package and file a claim against it, returning the client
+fn claimed_package<T: Config>() -> T::AccountId {
+	let client = insured_package::<T>();
+	InsuranceModule::<T>::file_claim(&client, &PACKAGE_ID, 500u32.into())
+		.expect("policy is active; qed");
+	client
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn pool_deposit() {
+		// Worst case prices shares against a pool the underwriter already holds shares in
+		let underwriter = deposit::<T>(0);
+		deposit::<T>(1);
+
+		#[extrinsic_call]
+		pool_deposit(RawOrigin::Signed(underwriter.clone()), 1_000_000u32.into());
+
+		assert_eq!(Shares::<T>::get(&underwriter), Some(2_000_000u32.into()));
+	}
+
+	#[benchmark]
+	fn pool_withdraw() {
+		let underwriter = deposit::<T>(0);
+
+		#[extrinsic_call]
+		pool_withdraw(RawOrigin::Signed(underwriter.clone()), 500_000u32.into());
+
+		assert_eq!(Shares::<T>::get(&underwriter), Some(500_000u32.into()));
+	}
+
+	#[benchmark]
+	fn claim_file() {
+		let client = insured_package::<T>();
+
+		#[extrinsic_call]
+		claim_file(RawOrigin::Signed(client.clone()), PACKAGE_ID, 500u32.into());
+
+		assert!(Claims::<T>::contains_key(&client, &PACKAGE_ID));
+	}
+
+	#[benchmark]
+	fn claim_approve() -> Result<(), BenchmarkError> {
+		let origin =
+			T::ClaimOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let client = claimed_package::<T>();
+
+		#[extrinsic_call]
+		claim_approve(origin as T::RuntimeOrigin, client.clone(), PACKAGE_ID);
+
+		assert_eq!(
+			Policies::<T>::get(&client, &PACKAGE_ID).map(|policy| policy.status),
+			Some(PolicyStatus::Claimed)
+		);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn claim_reject() -> Result<(), BenchmarkError> {
+		let origin =
+			T::ClaimOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let client = claimed_package::<T>();
+
+		#[extrinsic_call]
+		claim_reject(origin as T::RuntimeOrigin, client.clone(), PACKAGE_ID);
+
+		assert_eq!(
+			Claims::<T>::get(&client, &PACKAGE_ID).map(|claim| claim.status),
+			Some(ClaimStatus::Rejected)
+		);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn cover_expire() {
+		let client = insured_package::<T>();
+		InsuranceModule::<T>::conclude_cover(&client, &PACKAGE_ID);
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number() + T::ClaimPeriod::get() + 1u32.into(),
+		);
+		let caller = funded_account::<T>("caller", 0);
+
+		#[extrinsic_call]
+		cover_expire(RawOrigin::Signed(caller), client.clone(), PACKAGE_ID);
+
+		assert_eq!(
+			Policies::<T>::get(&client, &PACKAGE_ID).map(|policy| policy.status),
+			Some(PolicyStatus::Expired)
+		);
+	}
+
+	impl_benchmark_test_suite!(InsuranceModule, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,174 @@
+use frame_support::pallet_macros::*;
+
+#[pallet_section]
+mod calls {
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::pool_deposit())]
+		pub fn pool_deposit(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			// Shares are priced against the pool's value *before* this deposit
+			let shares = Self::shares_for_deposit(amount, Self::pool_value())?;
+
+			T::Currency::transfer(
+				&who,
+				&Self::pool_account(),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)
+			.map_err(|_| Error::<T>::InsufficientFunds)?;
+			PoolAssets::<T>::mutate(|assets| *assets = assets.saturating_add(amount));
+
+			Shares::<T>::mutate(&who, |held| {
+				*held = Some(held.unwrap_or_else(Zero::zero).saturating_add(shares))
+			});
+			TotalShares::<T>::mutate(|total| *total = total.saturating_add(shares));
+
+			Self::deposit_event(Event::Deposited { underwriter: who, amount, shares });
+
+			Ok(())
+		}
+
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::pool_withdraw())]
+		pub fn pool_withdraw(origin: OriginFor<T>, shares: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let held = Self::shares_of(&who).unwrap_or_else(Zero::zero);
+			ensure!(shares <= held, Error::<T>::InsufficientShares);
+
+			// Funds backing active cover can not be withdrawn
+			let amount = Self::value_of_shares(shares)?;
+			ensure!(amount <= Self::free_capacity(), Error::<T>::InsufficientPoolCapacity);
+
+			T::Currency::transfer(
+				&Self::pool_account(),
+				&who,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			PoolAssets::<T>::mutate(|assets| *assets = assets.saturating_sub(amount));
+
+			let remaining = held.saturating_sub(shares);
+			if remaining.is_zero() {
+				Shares::<T>::remove(&who);
+			} else {
+				Shares::<T>::insert(&who, remaining);
+			}
+			TotalShares::<T>::mutate(|total| *total = total.saturating_sub(shares));
+
+			Self::deposit_event(Event::Withdrawn { underwriter: who, amount, shares });
+
+			Ok(())
+		}
+
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::claim_file())]
+		pub fn claim_file(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::file_claim(&who, &package_id, amount)
+		}
+
+		#[pallet::call_index(30)]
+		#[pallet::weight(T::WeightInfo::claim_approve())]
+		pub fn claim_approve(
+			origin: OriginFor<T>,
+			client: T::AccountId,
+			package_id: PackageId,
+		) -> DispatchResult {
+			T::ClaimOrigin::ensure_origin(origin)?;
+
+			let mut claim =
+				Claims::<T>::get(&client, &package_id).ok_or(Error::<T>::ClaimDoesNotExist)?;
+			ensure!(claim.status == ClaimStatus::Pending, Error::<T>::ClaimNotPending);
+
+			let mut policy =
+				Policies::<T>::get(&client, &package_id).ok_or(Error::<T>::PolicyDoesNotExist)?;
+			ensure!(policy.status == PolicyStatus::Active, Error::<T>::PolicyNotActive);
+
+			// Pay the claim out of the pool
+			T::Currency::transfer(
+				&Self::pool_account(),
+				&client,
+				claim.amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			PoolAssets::<T>::mutate(|assets| *assets = assets.saturating_sub(claim.amount));
+
+			Self::release_cover(&mut policy, PolicyStatus::Claimed);
+			Policies::<T>::insert(&client, &package_id, policy);
+
+			claim.status = ClaimStatus::Approved;
+			let payout = claim.amount;
+			Claims::<T>::insert(&client, &package_id, claim);
+
+			Self::deposit_event(Event::ClaimApproved { client, package: package_id, payout });
+
+			Ok(())
+		}
+
+		#[pallet::call_index(40)]
+		#[pallet::weight(T::WeightInfo::claim_reject())]
+		pub fn claim_reject(
+			origin: OriginFor<T>,
+			client: T::AccountId,
+			package_id: PackageId,
+		) -> DispatchResult {
+			T::ClaimOrigin::ensure_origin(origin)?;
+
+			let mut claim =
+				Claims::<T>::get(&client, &package_id).ok_or(Error::<T>::ClaimDoesNotExist)?;
+			ensure!(claim.status == ClaimStatus::Pending, Error::<T>::ClaimNotPending);
+
+			claim.status = ClaimStatus::Rejected;
+			Claims::<T>::insert(&client, &package_id, claim);
+
+			Self::deposit_event(Event::ClaimRejected { client, package: package_id });
+
+			Ok(())
+		}
+
+		#[pallet::call_index(50)]
+		#[pallet::weight(T::WeightInfo::cover_expire())]
+		pub fn cover_expire(
+			origin: OriginFor<T>,
+			client: T::AccountId,
+			package_id: PackageId,
+		) -> DispatchResult {
+			// Anyone can expire a policy whose claim period has passed
+			ensure_signed(origin)?;
+
+			let mut policy =
+				Policies::<T>::get(&client, &package_id).ok_or(Error::<T>::PolicyDoesNotExist)?;
+			ensure!(policy.status == PolicyStatus::Active, Error::<T>::PolicyNotActive);
+			ensure!(
+				policy.expires_on.map_or(false, |expires_on| {
+					<frame_system::Pallet<T>>::block_number() > expires_on
+				}),
+				Error::<T>::ClaimPeriodNotOver
+			);
+
+			// A pending claim must be decided before the policy can expire
+			ensure!(
+				Claims::<T>::get(&client, &package_id)
+					.map_or(true, |claim| claim.status != ClaimStatus::Pending),
+				Error::<T>::ClaimExists
+			);
+
+			Self::release_cover(&mut policy, PolicyStatus::Expired);
+			Policies::<T>::insert(&client, &package_id, policy);
+
+			Self::deposit_event(Event::CoverExpired { client, package: package_id });
+
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,49 @@
+use frame_support::pallet_macros::*;
+
+#[pallet_section]
+mod config {
+
+	use frame_support::{
+		sp_runtime::Permill,
+		traits::{Currency, ReservableCurrency},
+		PalletId,
+	};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The currency trait.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Used to derive the account holding the pool's funds
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Premium charged on the insured value of every shipment
+		#[pallet::constant]
+		type BaseRate: Get<Permill>;
+
+		/// Additional premium for shipments containing hazardous materials
+		#[pallet::constant]
+		type HazardousMaterialsSurcharge: Get<Permill>;
+
+		/// Additional premium for shipments which must be climate controlled
+		#[pallet::constant]
+		type ClimateControlledSurcharge: Get<Permill>;
+
+		/// Number of blocks after a shipment concludes during which a claim can be filed
+		#[pallet::constant]
+		type ClaimPeriod: Get<BlockNumberFor<Self>>;
+
+		/// Origin which can approve or reject claims
+		type ClaimOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+}
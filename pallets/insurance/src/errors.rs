@@ -0,0 +1,34 @@
+use frame_support::pallet_macros::*;
+
+#[pallet_section]
+mod errors {
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Package already has a policy
+		PolicyExists,
+		/// Package does not have a policy
+		PolicyDoesNotExist,
+		/// Policy has expired, been claimed against, or is otherwise not active
+		PolicyNotActive,
+		/// A claim has already been filed against that policy
+		ClaimExists,
+		/// A claim has not been filed against that policy
+		ClaimDoesNotExist,
+		/// Claim has already been approved or rejected
+		ClaimNotPending,
+		/// Claim amount exceeds the insured value
+		ClaimExceedsInsuredValue,
+		/// Insured value must be greater than zero
+		InvalidInsuredValue,
+		/// Pool does not hold enough uncommitted funds to back the requested cover
+		InsufficientPoolCapacity,
+		/// Not enough funds available to perform the requested action
+		InsufficientFunds,
+		/// Underwriter does not hold enough shares
+		InsufficientShares,
+		/// Policy can not be expired until its claim period has passed
+		ClaimPeriodNotOver,
+		/// Arithmetic overflow in pool accounting
+		Overflow,
+	}
+}
@@ -0,0 +1,28 @@
+use frame_support::pallet_macros::*;
+
+#[pallet_section]
+mod events {
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Cover purchased for a package [Client ID, Package ID, Insured Value, Premium]
+		CoverPurchased {
+			client: T::AccountId,
+			package: PackageId,
+			insured_value: BalanceOf<T>,
+			premium: BalanceOf<T>,
+		},
+		/// Cover has expired without a claim [Client ID, Package ID]
+		CoverExpired { client: T::AccountId, package: PackageId },
+		/// Funds deposited into the pool [Underwriter ID, Amount, Shares]
+		Deposited { underwriter: T::AccountId, amount: BalanceOf<T>, shares: BalanceOf<T> },
+		/// Funds withdrawn from the pool [Underwriter ID, Amount, Shares]
+		Withdrawn { underwriter: T::AccountId, amount: BalanceOf<T>, shares: BalanceOf<T> },
+		/// Claim filed against a policy [Client ID, Package ID, Amount]
+		ClaimFiled { client: T::AccountId, package: PackageId, amount: BalanceOf<T> },
+		/// Claim approved and paid out of the pool [Client ID, Package ID, Payout]
+		ClaimApproved { client: T::AccountId, package: PackageId, payout: BalanceOf<T> },
+		/// Claim rejected [Client ID, Package ID]
+		ClaimRejected { client: T::AccountId, package: PackageId },
+	}
+}
@@ -0,0 +1,324 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+mod calls;
+mod config;
+mod errors;
+mod events;
+pub mod types;
+
+use crate::types::*;
+use frame_support::{
+	pallet_macros::*,
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{AccountIdConversion, Saturating, Zero},
+		SaturatedConversion,
+	},
+	traits::{Currency, ExistenceRequirement},
+};
+use frame_system::pallet_prelude::*;
+
+#[import_section(events::events)]
+#[import_section(errors::errors)]
+#[import_section(config::config)]
+#[import_section(calls::calls)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_policy)]
+	pub type Policies<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId, // Client's account id
+		Blake2_128Concat,
+		PackageId,
+		Policy<T>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_claim)]
+	pub type Claims<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId, // Client's account id
+		Blake2_128Concat,
+		PackageId,
+		Claim<T>,
+	>;
+
+	/// Pool shares held by each underwriter
+	#[pallet::storage]
+	#[pallet::getter(fn shares_of)]
+	pub type Shares<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>>;
+
+	/// Total pool shares in issue
+	#[pallet::storage]
+	#[pallet::getter(fn total_shares)]
+	pub type TotalShares<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Sum of the insured value of every policy which can still be claimed against
+	#[pallet::storage]
+	#[pallet::getter(fn active_cover)]
+	pub type ActiveCover<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Funds deposited into the pool or earned from premiums, less withdrawals and payouts.
+	/// Kept apart from the pool account's balance so that transfers made straight to the
+	/// account can not move the price of shares.
+	#[pallet::storage]
+	#[pallet::getter(fn pool_value)]
+	pub type PoolAssets<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(
+			_n: BlockNumberFor<T>,
+		) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the pool's funds
+	pub fn pool_account() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// Funds in the pool which are not backing any active cover
+	pub fn free_capacity() -> BalanceOf<T> {
+		Self::pool_value().saturating_sub(Self::active_cover())
+	}
+
+	/// Premium charged to insure a shipment for `insured_value`
+	pub fn premium_for(
+		insured_value: BalanceOf<T>,
+		hazardous_materials: bool,
+		climate_controlled: bool,
+	) -> BalanceOf<T> {
+		let mut premium = T::BaseRate::get() * insured_value;
+
+		if hazardous_materials {
+			premium = premium.saturating_add(T::HazardousMaterialsSurcharge::get() * insured_value);
+		}
+
+		if climate_controlled {
+			premium = premium.saturating_add(T::ClimateControlledSurcharge::get() * insured_value);
+		}
+
+		premium
+	}
+
+	/// Shares minted for depositing `amount` into a pool worth `pool_value`
+	pub fn shares_for_deposit(
+		amount: BalanceOf<T>,
+		pool_value: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let total_shares = Self::total_shares();
+
+		// The first underwriter (or anyone topping up an empty pool) receives shares 1:1
+		if total_shares.is_zero() || pool_value.is_zero() {
+			return Ok(amount)
+		}
+
+		let shares = amount
+			.saturated_into::<u128>()
+			.checked_mul(total_shares.saturated_into::<u128>())
+			.ok_or(Error::<T>::Overflow)? /
+			pool_value.saturated_into::<u128>();
+
+		Ok(shares.saturated_into())
+	}
+
+	/// Funds paid out for redeeming `shares` from the pool
+	pub fn value_of_shares(shares: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+		let total_shares = Self::total_shares();
+
+		if total_shares.is_zero() {
+			return Ok(Zero::zero())
+		}
+
+		let value = shares
+			.saturated_into::<u128>()
+			.checked_mul(Self::pool_value().saturated_into::<u128>())
+			.ok_or(Error::<T>::Overflow)? /
+			total_shares.saturated_into::<u128>();
+
+		Ok(value.saturated_into())
+	}
+
+	/// Buy cover for a package, transferring the premium from the client into the pool.
+	/// Returns the premium paid.
+	pub fn purchase_cover(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+		insured_value: BalanceOf<T>,
+		hazardous_materials: bool,
+		climate_controlled: bool,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		ensure!(!insured_value.is_zero(), Error::<T>::InvalidInsuredValue);
+		ensure!(!Policies::<T>::contains_key(&client, &package_id), Error::<T>::PolicyExists);
+
+		// Every policy must be fully backed by funds in the pool
+		ensure!(insured_value <= Self::free_capacity(), Error::<T>::InsufficientPoolCapacity);
+
+		let premium = Self::premium_for(insured_value, hazardous_materials, climate_controlled);
+
+		T::Currency::transfer(
+			&client,
+			&Self::pool_account(),
+			premium,
+			ExistenceRequirement::KeepAlive,
+		)
+		.map_err(|_| Error::<T>::InsufficientFunds)?;
+		PoolAssets::<T>::mutate(|assets| *assets = assets.saturating_add(premium));
+
+		Policies::<T>::insert(
+			&client,
+			&package_id,
+			Policy::new(*package_id, client.clone(), carrier.clone(), insured_value, premium),
+		);
+		ActiveCover::<T>::mutate(|cover| *cover = cover.saturating_add(insured_value));
+
+		Self::deposit_event(Event::<T>::CoverPurchased {
+			client: client.clone(),
+			package: *package_id,
+			insured_value,
+			premium,
+		});
+
+		Ok(premium)
+	}
+
+	/// Start the claim period for a package's policy once the shipment has concluded.
+	/// Packages without cover are ignored.
+	pub fn conclude_cover(client: &T::AccountId, package_id: &PackageId) {
+		Policies::<T>::mutate(&client, &package_id, |policy| {
+			if let Some(policy) = policy {
+				if policy.status == PolicyStatus::Active && policy.expires_on.is_none() {
+					policy.expires_on = Some(
+						<frame_system::Pallet<T>>::block_number()
+							.saturating_add(T::ClaimPeriod::get()),
+					);
+				}
+			}
+		});
+	}
+
+	/// Remove a package's policy and claim once the package itself is removed.
+	/// Cover that is still active is released, and an undecided claim lapses.
+	pub fn remove_cover(client: &T::AccountId, package_id: &PackageId) {
+		if let Some(policy) = Policies::<T>::take(&client, &package_id) {
+			if policy.status == PolicyStatus::Active {
+				ActiveCover::<T>::mutate(|cover| {
+					*cover = cover.saturating_sub(policy.insured_value)
+				});
+			}
+		}
+		Claims::<T>::remove(&client, &package_id);
+	}
+
+	/// File a claim against a package's policy for `amount`, to be decided by `ClaimOrigin`
+	pub fn file_claim(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		let policy =
+			Policies::<T>::get(&client, &package_id).ok_or(Error::<T>::PolicyDoesNotExist)?;
+
+		// Ensure policy is active and still within its claim period
+		ensure!(policy.status == PolicyStatus::Active, Error::<T>::PolicyNotActive);
+		ensure!(
+			policy.expires_on.map_or(true, |expires_on| {
+				<frame_system::Pallet<T>>::block_number() <= expires_on
+			}),
+			Error::<T>::PolicyNotActive
+		);
+
+		ensure!(amount <= policy.insured_value, Error::<T>::ClaimExceedsInsuredValue);
+
+		// Rejected claims may be refiled, pending or approved ones may not
+		ensure!(
+			Claims::<T>::get(&client, &package_id)
+				.map_or(true, |claim| claim.status == ClaimStatus::Rejected),
+			Error::<T>::ClaimExists
+		);
+
+		Claims::<T>::insert(
+			&client,
+			&package_id,
+			Claim {
+				amount,
+				filed_on: <frame_system::Pallet<T>>::block_number(),
+				status: ClaimStatus::Pending,
+			},
+		);
+
+		Self::deposit_event(Event::ClaimFiled {
+			client: client.clone(),
+			package: *package_id,
+			amount,
+		});
+
+		Ok(())
+	}
+
+	/// Release the pool capacity backing a policy
+	fn release_cover(policy: &mut Policy<T>, status: PolicyStatus) {
+		policy.status = status;
+		ActiveCover::<T>::mutate(|cover| *cover = cover.saturating_sub(policy.insured_value));
+	}
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config> Pallet<T> {
+	/// Check that shares add up and the pool's assets are held by the pool and back its cover
+	pub fn do_try_state() -> Result<(), DispatchError> {
+		use frame_support::sp_runtime::traits::CheckedAdd;
+
+		let shares = Shares::<T>::iter_values()
+			.try_fold(BalanceOf::<T>::zero(), |total, shares| total.checked_add(&shares))
+			.ok_or(Error::<T>::Overflow)?;
+		ensure!(shares == Self::total_shares(), "Shares do not add up to the total in issue");
+
+		ensure!(
+			Self::pool_value() <= T::Currency::free_balance(&Self::pool_account()),
+			"Pool assets are not held by the pool account"
+		);
+		ensure!(Self::active_cover() <= Self::pool_value(), "Active cover is not fully backed");
+
+		let cover = Policies::<T>::iter_values()
+			.filter(|policy| policy.status == PolicyStatus::Active)
+			.try_fold(BalanceOf::<T>::zero(), |total, policy| {
+				total.checked_add(&policy.insured_value)
+			})
+			.ok_or(Error::<T>::Overflow)?;
+		ensure!(cover == Self::active_cover(), "Active cover does not match active policies");
+
+		Ok(())
+	}
+}
@@ -0,0 +1,117 @@
+use crate as pallet_insurance;
+use frame_support::{
+	parameter_types,
+	traits::{ConstU16, ConstU64},
+	PalletId,
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage, Permill,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		InsuranceModule: pallet_insurance,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = ();
+	type MaxHolds = ();
+}
+
+parameter_types! {
+	pub const InsurancePalletId: PalletId = PalletId(*b"lgx/insr");
+	pub const BaseRate: Permill = Permill::from_percent(1);
+	pub const HazardousMaterialsSurcharge: Permill = Permill::from_percent(2);
+	pub const ClimateControlledSurcharge: Permill = Permill::from_percent(3);
+}
+
+impl pallet_insurance::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type PalletId = InsurancePalletId;
+	type BaseRate = BaseRate;
+	type HazardousMaterialsSurcharge = HazardousMaterialsSurcharge;
+	type ClimateControlledSurcharge = ClimateControlledSurcharge;
+	type ClaimPeriod = ConstU64<20>;
+	type ClaimOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+pub const CLIENT: u64 = 1;
+pub const CARRIER: u64 = 2;
+pub const UNDERWRITER: u64 = 3;
+pub const OTHER_UNDERWRITER: u64 = 4;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(CLIENT, 1_000),
+			(CARRIER, 1_000),
+			(UNDERWRITER, 10_000),
+			(OTHER_UNDERWRITER, 10_000),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Run a test and check the pallet's invariants once it has finished
+pub fn build_and_execute(test: impl FnOnce()) {
+	new_test_ext().execute_with(|| {
+		test();
+		InsuranceModule::do_try_state().expect("state is consistent");
+	});
+}
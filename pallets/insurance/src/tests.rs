@@ -0,0 +1,294 @@
+use crate::{mock::*, types::*, Error, Event, Shares, TotalShares};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, ExistenceRequirement},
+};
+use sp_runtime::DispatchError::BadOrigin;
+
+const PACKAGE_ID: PackageId = [1u8; 32];
+
+fn deposit(underwriter: u64, amount: u64) {
+	assert_ok!(InsuranceModule::pool_deposit(RuntimeOrigin::signed(underwriter), amount));
+}
+
+fn insure(insured_value: u64) {
+	assert_ok!(InsuranceModule::purchase_cover(
+		&CLIENT,
+		&PACKAGE_ID,
+		&CARRIER,
+		insured_value,
+		false,
+		false
+	));
+}
+
+fn policy_status() -> Option<PolicyStatus> {
+	InsuranceModule::get_policy(CLIENT, PACKAGE_ID).map(|policy| policy.status)
+}
+
+#[test]
+fn first_deposit_mints_shares_one_to_one() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+
+		assert_eq!(Shares::<Test>::get(UNDERWRITER), Some(1_000));
+		assert_eq!(TotalShares::<Test>::get(), 1_000);
+		assert_eq!(InsuranceModule::pool_value(), 1_000);
+		assert_eq!(Balances::free_balance(InsuranceModule::pool_account()), 1_000);
+		System::assert_last_event(
+			Event::Deposited { underwriter: UNDERWRITER, amount: 1_000, shares: 1_000 }.into(),
+		);
+	});
+}
+
+#[test]
+fn premiums_raise_the_value_of_shares() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+		insure(500);
+		assert_eq!(InsuranceModule::pool_value(), 1_005);
+
+		// Later underwriters pay the higher share price
+		deposit(OTHER_UNDERWRITER, 1_005);
+		assert_eq!(Shares::<Test>::get(OTHER_UNDERWRITER), Some(1_000));
+		assert_eq!(InsuranceModule::value_of_shares(1_000), Ok(1_005));
+	});
+}
+
+#[test]
+fn donations_do_not_move_the_share_price() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+
+		// Funds sent straight to the pool account are not counted as pool assets
+		assert_ok!(<Balances as Currency<u64>>::transfer(
+			&UNDERWRITER,
+			&InsuranceModule::pool_account(),
+			5_000,
+			ExistenceRequirement::KeepAlive,
+		));
+		assert_eq!(InsuranceModule::pool_value(), 1_000);
+		assert_eq!(InsuranceModule::free_capacity(), 1_000);
+
+		deposit(OTHER_UNDERWRITER, 1_000);
+		assert_eq!(Shares::<Test>::get(OTHER_UNDERWRITER), Some(1_000));
+		assert_eq!(InsuranceModule::value_of_shares(1_000), Ok(1_000));
+	});
+}
+
+#[test]
+fn premium_includes_surcharges() {
+	build_and_execute(|| {
+		assert_eq!(InsuranceModule::premium_for(500, false, false), 5);
+		assert_eq!(InsuranceModule::premium_for(500, true, false), 15);
+		assert_eq!(InsuranceModule::premium_for(500, false, true), 20);
+		assert_eq!(InsuranceModule::premium_for(500, true, true), 30);
+	});
+}
+
+#[test]
+fn purchase_cover_requires_free_capacity() {
+	build_and_execute(|| {
+		assert_noop!(
+			InsuranceModule::purchase_cover(&CLIENT, &PACKAGE_ID, &CARRIER, 500, false, false),
+			Error::<Test>::InsufficientPoolCapacity
+		);
+
+		deposit(UNDERWRITER, 1_000);
+		assert_noop!(
+			InsuranceModule::purchase_cover(&CLIENT, &PACKAGE_ID, &CARRIER, 0, false, false),
+			Error::<Test>::InvalidInsuredValue
+		);
+
+		insure(500);
+		assert_eq!(InsuranceModule::active_cover(), 500);
+		assert_eq!(InsuranceModule::free_capacity(), 505);
+		assert_eq!(Balances::free_balance(CLIENT), 995);
+		assert_noop!(
+			InsuranceModule::purchase_cover(&CLIENT, &PACKAGE_ID, &CARRIER, 100, false, false),
+			Error::<Test>::PolicyExists
+		);
+	});
+}
+
+#[test]
+fn withdrawals_can_not_touch_funds_backing_cover() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+		insure(800);
+
+		assert_noop!(
+			InsuranceModule::pool_withdraw(RuntimeOrigin::signed(UNDERWRITER), 1_001),
+			Error::<Test>::InsufficientShares
+		);
+		assert_noop!(
+			InsuranceModule::pool_withdraw(RuntimeOrigin::signed(UNDERWRITER), 1_000),
+			Error::<Test>::InsufficientPoolCapacity
+		);
+
+		// 200 shares are worth 201 now that the premium has been earned
+		assert_ok!(InsuranceModule::pool_withdraw(RuntimeOrigin::signed(UNDERWRITER), 200));
+		assert_eq!(Shares::<Test>::get(UNDERWRITER), Some(800));
+		assert_eq!(TotalShares::<Test>::get(), 800);
+		assert_eq!(InsuranceModule::pool_value(), 807);
+		assert_eq!(Balances::free_balance(UNDERWRITER), 9_201);
+		System::assert_last_event(
+			Event::Withdrawn { underwriter: UNDERWRITER, amount: 201, shares: 200 }.into(),
+		);
+	});
+}
+
+#[test]
+fn withdrawing_every_share_removes_the_holding() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+
+		assert_ok!(InsuranceModule::pool_withdraw(RuntimeOrigin::signed(UNDERWRITER), 1_000));
+		assert_eq!(Shares::<Test>::get(UNDERWRITER), None);
+		assert_eq!(TotalShares::<Test>::get(), 0);
+		assert_eq!(InsuranceModule::pool_value(), 0);
+		assert_eq!(Balances::free_balance(UNDERWRITER), 10_000);
+	});
+}
+
+#[test]
+fn approved_claims_are_paid_from_the_pool() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+		insure(500);
+
+		assert_noop!(
+			InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 501),
+			Error::<Test>::ClaimExceedsInsuredValue
+		);
+		assert_ok!(InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 300));
+		assert_noop!(
+			InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 300),
+			Error::<Test>::ClaimExists
+		);
+
+		assert_noop!(
+			InsuranceModule::claim_approve(RuntimeOrigin::signed(CLIENT), CLIENT, PACKAGE_ID),
+			BadOrigin
+		);
+		assert_ok!(InsuranceModule::claim_approve(RuntimeOrigin::root(), CLIENT, PACKAGE_ID));
+
+		assert_eq!(policy_status(), Some(PolicyStatus::Claimed));
+		assert_eq!(
+			InsuranceModule::get_claim(CLIENT, PACKAGE_ID).map(|claim| claim.status),
+			Some(ClaimStatus::Approved)
+		);
+		assert_eq!(Balances::free_balance(CLIENT), 1_295);
+		assert_eq!(InsuranceModule::active_cover(), 0);
+		assert_eq!(InsuranceModule::pool_value(), 705);
+		System::assert_last_event(
+			Event::ClaimApproved { client: CLIENT, package: PACKAGE_ID, payout: 300 }.into(),
+		);
+
+		assert_noop!(
+			InsuranceModule::claim_approve(RuntimeOrigin::root(), CLIENT, PACKAGE_ID),
+			Error::<Test>::ClaimNotPending
+		);
+	});
+}
+
+#[test]
+fn rejected_claims_can_be_refiled() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+		insure(500);
+		assert_ok!(InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 300));
+
+		assert_ok!(InsuranceModule::claim_reject(RuntimeOrigin::root(), CLIENT, PACKAGE_ID));
+		assert_eq!(policy_status(), Some(PolicyStatus::Active));
+		assert_eq!(InsuranceModule::pool_value(), 1_005);
+		System::assert_last_event(
+			Event::ClaimRejected { client: CLIENT, package: PACKAGE_ID }.into(),
+		);
+
+		assert_ok!(InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 200));
+		assert_eq!(
+			InsuranceModule::get_claim(CLIENT, PACKAGE_ID)
+				.map(|claim| (claim.amount, claim.status)),
+			Some((200, ClaimStatus::Pending))
+		);
+	});
+}
+
+#[test]
+fn cover_expires_once_the_claim_period_has_passed() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+		insure(500);
+
+		// The claim period only starts once the shipment has concluded
+		assert_noop!(
+			InsuranceModule::cover_expire(RuntimeOrigin::signed(CARRIER), CLIENT, PACKAGE_ID),
+			Error::<Test>::ClaimPeriodNotOver
+		);
+		InsuranceModule::conclude_cover(&CLIENT, &PACKAGE_ID);
+
+		System::set_block_number(21);
+		assert_noop!(
+			InsuranceModule::cover_expire(RuntimeOrigin::signed(CARRIER), CLIENT, PACKAGE_ID),
+			Error::<Test>::ClaimPeriodNotOver
+		);
+
+		System::set_block_number(22);
+		assert_noop!(
+			InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 100),
+			Error::<Test>::PolicyNotActive
+		);
+		assert_ok!(InsuranceModule::cover_expire(
+			RuntimeOrigin::signed(CARRIER),
+			CLIENT,
+			PACKAGE_ID
+		));
+
+		assert_eq!(policy_status(), Some(PolicyStatus::Expired));
+		assert_eq!(InsuranceModule::active_cover(), 0);
+		assert_eq!(InsuranceModule::free_capacity(), 1_005);
+		System::assert_last_event(
+			Event::CoverExpired { client: CLIENT, package: PACKAGE_ID }.into(),
+		);
+	});
+}
+
+#[test]
+fn pending_claims_must_be_decided_before_cover_expires() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+		insure(500);
+		InsuranceModule::conclude_cover(&CLIENT, &PACKAGE_ID);
+		assert_ok!(InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 300));
+
+		System::set_block_number(22);
+		assert_noop!(
+			InsuranceModule::cover_expire(RuntimeOrigin::signed(CARRIER), CLIENT, PACKAGE_ID),
+			Error::<Test>::ClaimExists
+		);
+
+		assert_ok!(InsuranceModule::claim_reject(RuntimeOrigin::root(), CLIENT, PACKAGE_ID));
+		assert_ok!(InsuranceModule::cover_expire(
+			RuntimeOrigin::signed(CARRIER),
+			CLIENT,
+			PACKAGE_ID
+		));
+	});
+}
+
+#[test]
+fn removing_cover_releases_capacity_and_drops_claims() {
+	build_and_execute(|| {
+		deposit(UNDERWRITER, 1_000);
+		insure(500);
+		InsuranceModule::conclude_cover(&CLIENT, &PACKAGE_ID);
+		assert_ok!(InsuranceModule::claim_file(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, 300));
+
+		InsuranceModule::remove_cover(&CLIENT, &PACKAGE_ID);
+
+		assert_eq!(policy_status(), None);
+		assert!(InsuranceModule::get_claim(CLIENT, PACKAGE_ID).is_none());
+		assert_eq!(InsuranceModule::active_cover(), 0);
+	});
+}
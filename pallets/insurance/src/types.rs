@@ -0,0 +1,64 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::sp_runtime::RuntimeDebug;
+use frame_system::{self as system, pallet_prelude::BlockNumberFor};
+use scale_info::TypeInfo;
+
+use crate::{pallet, BalanceOf};
+
+pub use logistics_primitives::PackageId;
+
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum PolicyStatus {
+	Active,
+	Claimed,
+	Expired,
+}
+
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum ClaimStatus {
+	Pending,
+	Approved,
+	Rejected,
+}
+
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+pub struct Policy<T: pallet::Config> {
+	pub package: PackageId,
+	pub client: T::AccountId,
+	pub carrier: T::AccountId,
+	pub insured_value: BalanceOf<T>,
+	pub premium: BalanceOf<T>,
+	pub purchased_on: BlockNumberFor<T>,
+	pub expires_on: Option<BlockNumberFor<T>>,
+	pub status: PolicyStatus,
+}
+
+impl<T: pallet::Config> Policy<T> {
+	pub fn new(
+		package: PackageId,
+		client: T::AccountId,
+		carrier: T::AccountId,
+		insured_value: BalanceOf<T>,
+		premium: BalanceOf<T>,
+	) -> Self {
+		Policy {
+			package,
+			client,
+			carrier,
+			insured_value,
+			premium,
+			purchased_on: <system::Pallet<T>>::block_number(),
+			expires_on: None,
+			status: PolicyStatus::Active,
+		}
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+pub struct Claim<T: pallet::Config> {
+	pub amount: BalanceOf<T>,
+	pub filed_on: BlockNumberFor<T>,
+	pub status: ClaimStatus,
+}
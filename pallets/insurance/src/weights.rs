@@ -0,0 +1,129 @@
+//! Weights for pallet_insurance
+//!
+//! THESE ARE HAND-WRITTEN ESTIMATES, NOT BENCHMARK RESULTS. Storage access counts are
+//! exact, execution times are placeholders. Regenerate on reference hardware with
+//! `scripts/benchmark.sh` before relying on them.
+
+// Command to regenerate:
+// ./target/release/node-template
+// benchmark
+// pallet
+// --chain
+// dev
+// --pallet
+// pallet_insurance
+// --extrinsic
+// *
+// --steps=50
+// --repeat=20
+// --wasm-execution=compiled
+// --output
+// pallets/insurance/src/weights.rs
+// --template
+// .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_insurance.
+pub trait WeightInfo {
+	fn pool_deposit() -> Weight;
+	fn pool_withdraw() -> Weight;
+	fn claim_file() -> Weight;
+	fn claim_approve() -> Weight;
+	fn claim_reject() -> Weight;
+	fn cover_expire() -> Weight;
+}
+
+/// Weights for pallet_insurance using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: InsuranceModule TotalShares (r:1 w:1)
+	/// Storage: InsuranceModule PoolAssets (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
+	/// Storage: InsuranceModule Shares (r:1 w:1)
+	fn pool_deposit() -> Weight {
+		Weight::from_parts(40_000_000, 6_200)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: InsuranceModule Shares (r:1 w:1)
+	/// Storage: InsuranceModule TotalShares (r:1 w:1)
+	/// Storage: InsuranceModule PoolAssets (r:1 w:1)
+	/// Storage: InsuranceModule ActiveCover (r:1 w:0)
+	/// Storage: System Account (r:2 w:2)
+	fn pool_withdraw() -> Weight {
+		Weight::from_parts(42_000_000, 6_200)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: InsuranceModule Policies (r:1 w:0)
+	/// Storage: InsuranceModule Claims (r:1 w:1)
+	fn claim_file() -> Weight {
+		Weight::from_parts(18_000_000, 3_700)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: InsuranceModule Claims (r:1 w:1)
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
+	/// Storage: InsuranceModule PoolAssets (r:1 w:1)
+	/// Storage: InsuranceModule ActiveCover (r:1 w:1)
+	fn claim_approve() -> Weight {
+		Weight::from_parts(45_000_000, 6_200)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: InsuranceModule Claims (r:1 w:1)
+	fn claim_reject() -> Weight {
+		Weight::from_parts(15_000_000, 3_600)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: InsuranceModule Claims (r:1 w:0)
+	/// Storage: InsuranceModule ActiveCover (r:1 w:1)
+	fn cover_expire() -> Weight {
+		Weight::from_parts(20_000_000, 3_700)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn pool_deposit() -> Weight {
+		Weight::from_parts(40_000_000, 6_200)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	fn pool_withdraw() -> Weight {
+		Weight::from_parts(42_000_000, 6_200)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	fn claim_file() -> Weight {
+		Weight::from_parts(18_000_000, 3_700)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn claim_approve() -> Weight {
+		Weight::from_parts(45_000_000, 6_200)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	fn claim_reject() -> Weight {
+		Weight::from_parts(15_000_000, 3_600)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn cover_expire() -> Weight {
+		Weight::from_parts(20_000_000, 3_700)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
@@ -0,0 +1,10 @@
+//! Applies arbitrary sequences of marketplace calls to the mock runtime,
+//! checking the marketplace's invariants after every call.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pallet_logistics::fuzzing::{run, Action};
+
+fuzz_target!(|actions: Vec<Action>| {
+	run(&actions);
+});
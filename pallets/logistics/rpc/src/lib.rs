@@ -0,0 +1,535 @@
+//! RPC interface for the logistics pallet.
+//!
+//! Results are returned as human-readable JSON: package IDs are hex encoded,
+//! accounts use their SS58 representation and fee amounts are encoded as
+//! `NumberOrHex` so that they survive JavaScript's number precision.
+
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
+
+use frame_system::pallet_prelude::BlockNumberFor;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_carrier::types::Proposal;
+use pallet_logistics::types::archive_key;
+use pallet_package::types::{Manifest, Package};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	offchain::{OffchainStorage, STORAGE_PREFIX},
+	Bytes, H256,
+};
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{traits::Block as BlockT, SaturatedConversion};
+
+pub use logistics_primitives::{runtime_api::LogisticsApi as LogisticsRuntimeApi, MAX_PAGE_SIZE};
+
+/// Number of items returned by paginated methods when no limit is given
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+#[rpc(client, server)]
+pub trait LogisticsApi<AccountId, BlockNumber, BlockHash> {
+	/// Packages created by a client which have not been pruned yet
+	#[method(name = "logistics_packagesOf")]
+	fn packages_of(
+		&self,
+		client: AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<PackageView<AccountId, BlockNumber>>>;
+
+	#[method(name = "logistics_package")]
+	fn package(
+		&self,
+		client: AccountId,
+		package_id: H256,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<PackageView<AccountId, BlockNumber>>>;
+
+	/// Package with the given derived ID, whichever client created it
+	#[method(name = "logistics_packageById")]
+	fn package_by_id(
+		&self,
+		package_id: H256,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<PackageView<AccountId, BlockNumber>>>;
+
+	/// Package a client knows by `reference` in their own systems, such as an ERP order number
+	#[method(name = "logistics_packageByReference")]
+	fn package_by_reference(
+		&self,
+		client: AccountId,
+		reference: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<PackageView<AccountId, BlockNumber>>>;
+
+	/// Metadata the client has attached to a package
+	#[method(name = "logistics_metadata")]
+	fn metadata(
+		&self,
+		client: AccountId,
+		package_id: H256,
+		at: Option<BlockHash>,
+	) -> RpcResult<BTreeMap<String, String>>;
+
+	#[method(name = "logistics_manifest")]
+	fn manifest(
+		&self,
+		client: AccountId,
+		package_id: H256,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<ManifestView>>;
+
+	/// Job board of packages waiting for a carrier
+	#[method(name = "logistics_openPackages")]
+	fn open_packages(
+		&self,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<PackageView<AccountId, BlockNumber>>>;
+
+	/// Packages assigned to a carrier which have not concluded yet
+	#[method(name = "logistics_carrierWorkload")]
+	fn carrier_workload(
+		&self,
+		carrier: AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<PackageView<AccountId, BlockNumber>>>;
+
+	/// Proposals made on a package, whatever their status
+	#[method(name = "logistics_proposalsFor")]
+	fn proposals_for(
+		&self,
+		client: AccountId,
+		package_id: H256,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<ProposalView<AccountId, BlockNumber>>>;
+
+	/// Proposals a carrier has made, across all clients
+	#[method(name = "logistics_proposalsByCarrier")]
+	fn proposals_by_carrier(
+		&self,
+		carrier: AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Page<ProposalView<AccountId, BlockNumber>>>;
+
+	/// Fee the carrier would be paid if the package were delivered at `delivered_at`
+	#[method(name = "logistics_quoteFinalFee")]
+	fn quote_final_fee(
+		&self,
+		client: AccountId,
+		package_id: H256,
+		carrier: AccountId,
+		delivered_at: BlockNumber,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<NumberOrHex>>;
+
+	/// SCALE encoded `ArchivedPackage` record of a pruned package.
+	///
+	/// Records are only written when the node runs with `--enable-offchain-indexing true`.
+	#[method(name = "logistics_archivedPackage")]
+	fn archived_package(&self, client: AccountId, package_id: H256) -> RpcResult<Option<Bytes>>;
+}
+
+/// A single page of results
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<Item> {
+	pub items: Vec<Item>,
+	/// Offset to request the next page with, or `None` once the last page has been returned.
+	/// A full page may be followed by an empty one.
+	pub next_offset: Option<u32>,
+}
+
+impl<Item> Page<Item> {
+	/// Page of `items` which the runtime returned for `offset` and `limit`
+	fn new(items: Vec<Item>, offset: u32, limit: u32) -> Self {
+		let returned = items.len() as u32;
+		let next_offset = (returned > 0 && returned >= limit).then(|| offset + returned);
+
+		Page { items, next_offset }
+	}
+}
+
+/// Offset and limit to ask the runtime for, filling in the defaults
+fn page_bounds(offset: Option<u32>, limit: Option<u32>) -> (u32, u32) {
+	(offset.unwrap_or(0), limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageView<AccountId, BlockNumber> {
+	pub id: H256,
+	pub client: AccountId,
+	pub carrier: Option<AccountId>,
+	pub pickup: String,
+	pub destination: String,
+	pub requested_on: BlockNumber,
+	pub collected_on: Option<BlockNumber>,
+	pub delivered_on: Option<BlockNumber>,
+	pub status: String,
+}
+
+impl<T: frame_system::Config> From<Package<T>> for PackageView<T::AccountId, BlockNumberFor<T>> {
+	fn from(package: Package<T>) -> Self {
+		PackageView {
+			id: package.id.into(),
+			client: package.client,
+			carrier: package.carrier,
+			pickup: String::from_utf8_lossy(&package.pickup).into_owned(),
+			destination: String::from_utf8_lossy(&package.destination).into_owned(),
+			requested_on: package.requested_on,
+			collected_on: package.collected_on,
+			delivered_on: package.delivered_on,
+			status: format!("{:?}", package.status),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestView {
+	pub description: String,
+	pub length: u32,
+	pub width: u32,
+	pub height: u32,
+	pub weight: u32,
+	pub hazardous_materials: bool,
+	pub climate_controlled: bool,
+	pub declared_value: NumberOrHex,
+}
+
+impl<T: pallet_package::Config> From<Manifest<T>> for ManifestView {
+	fn from(manifest: Manifest<T>) -> Self {
+		ManifestView {
+			description: String::from_utf8_lossy(&manifest.description).into_owned(),
+			length: manifest.dimensions.length,
+			width: manifest.dimensions.width,
+			height: manifest.dimensions.height,
+			weight: manifest.weight,
+			hazardous_materials: manifest.hazardous_materials,
+			climate_controlled: manifest.climate_controlled,
+			declared_value: manifest.declared_value.saturated_into::<u128>().into(),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalView<AccountId, BlockNumber> {
+	pub package: H256,
+	pub client: AccountId,
+	pub carrier: AccountId,
+	pub maximum_fee_amount: NumberOrHex,
+	pub minimum_fee_amount: NumberOrHex,
+	pub penalty_period: u32,
+	pub penalty_amount: NumberOrHex,
+	pub proposed_on: BlockNumber,
+	pub status: String,
+	pub deposit: NumberOrHex,
+}
+
+impl<T: pallet_carrier::Config> From<Proposal<T>>
+	for ProposalView<T::AccountId, BlockNumberFor<T>>
+{
+	fn from(proposal: Proposal<T>) -> Self {
+		ProposalView {
+			package: proposal.package.into(),
+			client: proposal.client,
+			carrier: proposal.carrier,
+			maximum_fee_amount: proposal.terms.maximum_fee_amount.saturated_into::<u128>().into(),
+			minimum_fee_amount: proposal.terms.minimum_fee_amount.saturated_into::<u128>().into(),
+			penalty_period: proposal.terms.penalty_period,
+			penalty_amount: proposal.terms.penalty_amount.saturated_into::<u128>().into(),
+			proposed_on: proposal.proposed_on,
+			status: format!("{:?}", proposal.status),
+			deposit: proposal.deposit.saturated_into::<u128>().into(),
+		}
+	}
+}
+
+/// Error codes returned by the logistics RPC methods
+pub enum Error {
+	/// The call to the runtime failed
+	RuntimeError,
+	/// The node has no offchain database to read archived packages from
+	OffchainStorageUnavailable,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::OffchainStorageUnavailable => 2,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Display) -> CallError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the logistics runtime API.",
+		Some(e.to_string()),
+	))
+}
+
+/// Serves logistics RPC requests from the runtime API and the node's offchain database.
+///
+/// `R` is the runtime the package, manifest and proposal types are instantiated with.
+pub struct Logistics<C, Block, R, S> {
+	client: Arc<C>,
+	storage: Option<S>,
+	_marker: PhantomData<(Block, R)>,
+}
+
+impl<C, Block, R, S> Logistics<C, Block, R, S> {
+	pub fn new(client: Arc<C>, storage: Option<S>) -> Self {
+		Self { client, storage, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, R, S> LogisticsApiServer<R::AccountId, BlockNumberFor<R>, Block::Hash>
+	for Logistics<C, Block, R, S>
+where
+	Block: BlockT,
+	R: pallet_package::Config + pallet_carrier::Config + Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: LogisticsRuntimeApi<
+		Block,
+		R::AccountId,
+		BlockNumberFor<R>,
+		pallet_carrier::BalanceOf<R>,
+		Package<R>,
+		Manifest<R>,
+		Proposal<R>,
+	>,
+	S: OffchainStorage + 'static,
+{
+	fn packages_of(
+		&self,
+		client: R::AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<PackageView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let (offset, limit) = page_bounds(offset, limit);
+		let packages = self
+			.client
+			.runtime_api()
+			.packages_of(at, client, offset, limit)
+			.map_err(runtime_error)?;
+
+		Ok(Page::new(
+			packages.into_iter().map(|(_, package)| package.into()).collect(),
+			offset,
+			limit,
+		))
+	}
+
+	fn package(
+		&self,
+		client: R::AccountId,
+		package_id: H256,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<PackageView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let package = self
+			.client
+			.runtime_api()
+			.package(at, client, package_id.0)
+			.map_err(runtime_error)?;
+
+		Ok(package.map(Into::into))
+	}
+
+	fn package_by_id(
+		&self,
+		package_id: H256,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<PackageView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let package = self
+			.client
+			.runtime_api()
+			.package_by_id(at, package_id.0)
+			.map_err(runtime_error)?;
+
+		Ok(package.map(|(_, package)| package.into()))
+	}
+
+	fn package_by_reference(
+		&self,
+		client: R::AccountId,
+		reference: String,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<PackageView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let api = self.client.runtime_api();
+
+		let Some(package_id) = api
+			.package_by_reference(at, client.clone(), reference.into_bytes())
+			.map_err(runtime_error)?
+		else {
+			return Ok(None)
+		};
+		let package = api.package(at, client, package_id).map_err(runtime_error)?;
+
+		Ok(package.map(Into::into))
+	}
+
+	fn metadata(
+		&self,
+		client: R::AccountId,
+		package_id: H256,
+		at: Option<Block::Hash>,
+	) -> RpcResult<BTreeMap<String, String>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let metadata = self
+			.client
+			.runtime_api()
+			.metadata(at, client, package_id.0)
+			.map_err(runtime_error)?;
+
+		Ok(metadata
+			.into_iter()
+			.map(|(key, value)| {
+				(
+					String::from_utf8_lossy(&key).into_owned(),
+					String::from_utf8_lossy(&value).into_owned(),
+				)
+			})
+			.collect())
+	}
+
+	fn manifest(
+		&self,
+		client: R::AccountId,
+		package_id: H256,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<ManifestView>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let manifest = self
+			.client
+			.runtime_api()
+			.manifest(at, client, package_id.0)
+			.map_err(runtime_error)?;
+
+		Ok(manifest.map(Into::into))
+	}
+
+	fn open_packages(
+		&self,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<PackageView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let (offset, limit) = page_bounds(offset, limit);
+		let packages = self
+			.client
+			.runtime_api()
+			.open_packages(at, offset, limit)
+			.map_err(runtime_error)?;
+
+		Ok(Page::new(packages.into_iter().map(Into::into).collect(), offset, limit))
+	}
+
+	fn carrier_workload(
+		&self,
+		carrier: R::AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<PackageView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let (offset, limit) = page_bounds(offset, limit);
+		let packages = self
+			.client
+			.runtime_api()
+			.carrier_workload(at, carrier, offset, limit)
+			.map_err(runtime_error)?;
+
+		Ok(Page::new(packages.into_iter().map(Into::into).collect(), offset, limit))
+	}
+
+	fn proposals_for(
+		&self,
+		client: R::AccountId,
+		package_id: H256,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<ProposalView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let (offset, limit) = page_bounds(offset, limit);
+		let proposals = self
+			.client
+			.runtime_api()
+			.proposals_for(at, client, package_id.0, offset, limit)
+			.map_err(runtime_error)?;
+
+		Ok(Page::new(proposals.into_iter().map(Into::into).collect(), offset, limit))
+	}
+
+	fn proposals_by_carrier(
+		&self,
+		carrier: R::AccountId,
+		offset: Option<u32>,
+		limit: Option<u32>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Page<ProposalView<R::AccountId, BlockNumberFor<R>>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let (offset, limit) = page_bounds(offset, limit);
+		let proposals = self
+			.client
+			.runtime_api()
+			.proposals_by_carrier(at, carrier, offset, limit)
+			.map_err(runtime_error)?;
+
+		Ok(Page::new(proposals.into_iter().map(Into::into).collect(), offset, limit))
+	}
+
+	fn quote_final_fee(
+		&self,
+		client: R::AccountId,
+		package_id: H256,
+		carrier: R::AccountId,
+		delivered_at: BlockNumberFor<R>,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<NumberOrHex>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let fee = self
+			.client
+			.runtime_api()
+			.quote_final_fee(at, client, package_id.0, carrier, delivered_at)
+			.map_err(runtime_error)?;
+
+		Ok(fee.map(|fee| fee.saturated_into::<u128>().into()))
+	}
+
+	fn archived_package(&self, client: R::AccountId, package_id: H256) -> RpcResult<Option<Bytes>> {
+		let storage = self.storage.as_ref().ok_or_else(|| {
+			CallError::Custom(ErrorObject::owned(
+				Error::OffchainStorageUnavailable.into(),
+				"Offchain storage is not available on this node.",
+				None::<()>,
+			))
+		})?;
+
+		Ok(storage
+			.get(STORAGE_PREFIX, &archive_key(&client, &package_id.0))
+			.map(Into::into))
+	}
+}
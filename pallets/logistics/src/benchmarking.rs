@@ -0,0 +1,662 @@
+//! Benchmarking setup for pallet-logistics
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+use crate::types::MarketplaceParameter;
+#[allow(unused)]
+use crate::Pallet as LogisticsModule;
+use frame_benchmarking::v2::*;
+use frame_support::{sp_runtime::traits::Bounded, traits::Hooks};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use pallet_package::types::{LineItemParams, PackageStatus};
+use sp_std::vec;
+
+const SEED: u32 = 0;
+const PACKAGE_ID: PackageId = [1u8; 32];
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let account: T::AccountId = account(name, index, SEED);
+	<T as pallet::Config>::Currency::make_free_balance_be(
+		&account,
+		BalanceOf::<T>::max_value() / 4u32.into(),
+	);
+	<T as pallet_package::Config>::Currency::make_free_balance_be(
+		&account,
+		pallet_package::BalanceOf::<T>::max_value() / 4u32.into(),
+	);
+	<T as pallet_carrier::Config>::Currency::make_free_balance_be(
+		&account,
+		pallet_carrier::BalanceOf::<T>::max_value() / 4u32.into(),
+	);
+	<T as pallet_insurance::Config>::Currency::make_free_balance_be(
+		&account,
+		pallet_insurance::BalanceOf::<T>::max_value() / 4u32.into(),
+	);
+	account
+}
+
+fn package_id(index: u32) -> PackageId {
+	let mut package_id = [0u8; 32];
+	package_id[..4].copy_from_slice(&index.to_le_bytes());
+	package_id
+}
+
+fn create_package<T: Config>(client: &T::AccountId, package_id: PackageId) {
+	LogisticsModule::<T>::package_create(
+		RawOrigin::Signed(client.clone()).into(),
+		Some(package_id),
+		vec![0u8; 256],
+		vec![0u8; 256],
+		vec![0u8; <T as pallet_package::Config>::DescriptionMaxLength::get() as usize],
+		100,
+		100,
+		100,
+		100,
+		true,
+		true,
+		100u32.into(),
+	)
+	.expect("client is funded; qed");
+}
+
+/// Submit a proposal on a package from each of `count` carriers, returning the first carrier
+fn propose<T: Config>(client: &T::AccountId, package_id: PackageId, count: u32) -> T::AccountId {
+	for index in 0..count {
+		LogisticsModule::<T>::proposal_create(
+			RawOrigin::Signed(funded_account::<T>("carrier", index)).into(),
+			client.clone(),
+			package_id,
+			1_000u32.into(),
+			100u32.into(),
+			10,
+			10u32.into(),
+		)
+		.expect("each carrier proposes once; qed");
+	}
+
+	account("carrier", 0, SEED)
+}
+
+/// Give the insurance pool enough capacity to cover another package
+fn fund_pool<T: Config>() {
+	pallet_insurance::Pallet::<T>::pool_deposit(
+		RawOrigin::Signed(funded_account::<T>("underwriter", 0)).into(),
+		1_000_000u32.into(),
+	)
+	.expect("underwriter is funded; qed");
+}
+
+/// Create an insured package with `proposals` proposals, collected by the first carrier
+fn collected_package<T: Config>(
+	client: &T::AccountId,
+	package_id: PackageId,
+	proposals: u32,
+) -> T::AccountId {
+	create_package::<T>(client, package_id);
+	insure_and_collect::<T>(client, package_id, proposals)
+}
+
+/// Insure a new package with `proposals` proposals and have it collected by the first carrier
+fn insure_and_collect<T: Config>(
+	client: &T::AccountId,
+	package_id: PackageId,
+	proposals: u32,
+) -> T::AccountId {
+	let carrier = propose::<T>(client, package_id, proposals);
+	fund_pool::<T>();
+
+	LogisticsModule::<T>::package_assign(
+		RawOrigin::Signed(client.clone()).into(),
+		package_id,
+		carrier.clone(),
+		Some(100u32.into()),
+	)
+	.expect("proposal is valid; qed");
+	LogisticsModule::<T>::package_collect(
+		RawOrigin::Signed(carrier.clone()).into(),
+		package_id,
+		client.clone(),
+	)
+	.expect("package is assigned to carrier; qed");
+
+	carrier
+}
+
+/// Deliver a collected package, concluding it
+fn deliver_package<T: Config>(client: &T::AccountId, package_id: PackageId, carrier: T::AccountId) {
+	LogisticsModule::<T>::package_deliver(
+		RawOrigin::Signed(client.clone()).into(),
+		package_id,
+		carrier,
+	)
+	.expect("package is in transit; qed");
+}
+
+/// Charge the highest commission governance may set, so deliveries also pay the marketplace
+fn charge_commission<T: Config>() {
+	Parameters::<T>::mutate(|parameters| {
+		parameters.commission_rate = <T as Config>::MaximumParameters::get().commission_rate
+	});
+}
+
+/// First block at which packages concluded now may be pruned
+fn past_retention<T: Config>() -> BlockNumberFor<T> {
+	frame_system::Pallet::<T>::block_number() +
+		<T as pallet_package::Config>::ConcludedRetentionPeriod::get() +
+		1u32.into()
+}
+
+fn assert_status<T: Config>(client: &T::AccountId, status: PackageStatus) {
+	assert_eq!(
+		T::Packages::package(client, &PACKAGE_ID).map(|package| package.status),
+		Some(status)
+	);
+}
+
+/// `package_assign` of this pallet
+fn pause_target<T: Config>() -> (Name, Option<Name>) {
+	let pallet_name = <LogisticsModule<T> as PalletInfoAccess>::name().as_bytes().to_vec();
+	(
+		pallet_name.try_into().expect("pallet name is short; qed"),
+		Some(b"package_assign".to_vec().try_into().expect("call name is short; qed")),
+	)
+}
+
+/// Line items with the longest permitted SKU and description
+fn line_items<T: Config>(count: u32) -> Vec<LineItemParams<pallet_package::BalanceOf<T>>> {
+	(0..count)
+		.map(|index| {
+			let mut sku = vec![0u8; <T as pallet_package::Config>::MaxSkuLength::get() as usize];
+			sku[..4].copy_from_slice(&index.to_le_bytes());
+			(
+				sku,
+				vec![0u8; <T as pallet_package::Config>::DescriptionMaxLength::get() as usize],
+				10,
+				10,
+				10u32.into(),
+			)
+		})
+		.collect()
+}
+
+/// Give a new package `count` line items
+fn itemise_package<T: Config>(client: &T::AccountId, package_id: PackageId, count: u32) {
+	LogisticsModule::<T>::package_set_line_items(
+		RawOrigin::Signed(client.clone()).into(),
+		package_id,
+		line_items::<T>(count),
+	)
+	.expect("package is new; qed");
+}
+
+/// Longest permitted metadata key, distinguished by `index`
+fn metadata_key<T: Config>(index: u32) -> Vec<u8> {
+	let mut key = vec![0u8; <T as pallet_package::Config>::MaxMetadataKeyLength::get() as usize];
+	key[..4].copy_from_slice(&index.to_le_bytes());
+	key
+}
+
+fn metadata_value<T: Config>() -> Vec<u8> {
+	vec![0u8; <T as pallet_package::Config>::MaxMetadataValueLength::get() as usize]
+}
+
+/// Fill all but one of a package's metadata entries and give it the longest external reference
+fn tag_package<T: Config>(client: &T::AccountId, package_id: PackageId) {
+	for index in 1..<T as pallet_package::Config>::MaxMetadataEntries::get() {
+		T::Packages::set_metadata(
+			client,
+			&package_id,
+			metadata_key::<T>(index),
+			metadata_value::<T>(),
+		)
+		.expect("package has room for more metadata; qed");
+	}
+	T::Packages::set_external_reference(
+		client,
+		&package_id,
+		Some(vec![1u8; <T as pallet_package::Config>::MaxReferenceLength::get() as usize]),
+	)
+	.expect("reference is not in use; qed");
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn package_assign(
+		p: Linear<1, { <T as pallet_carrier::Config>::MaxProposalsPerPackage::get() }>,
+	) {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		let carrier = propose::<T>(&client, PACKAGE_ID, p);
+		fund_pool::<T>();
+
+		#[extrinsic_call]
+		package_assign(
+			RawOrigin::Signed(client.clone()),
+			PACKAGE_ID,
+			carrier,
+			Some(100u32.into()),
+		);
+
+		assert_status::<T>(&client, PackageStatus::Assigned);
+	}
+
+	#[benchmark]
+	fn package_cancel() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+
+		#[extrinsic_call]
+		package_cancel(RawOrigin::Signed(client.clone()), PACKAGE_ID);
+
+		assert_status::<T>(&client, PackageStatus::Cancelled);
+	}
+
+	#[benchmark]
+	fn package_deliver() {
+		let client = funded_account::<T>("client", 0);
+		let carrier = collected_package::<T>(&client, PACKAGE_ID, 1);
+
+		charge_commission::<T>();
+
+		// Late delivery, so penalties are deducted from the fee
+		frame_system::Pallet::<T>::set_block_number(100u32.into());
+
+		#[extrinsic_call]
+		package_deliver(RawOrigin::Signed(client.clone()), PACKAGE_ID, carrier);
+
+		assert_status::<T>(&client, PackageStatus::Delivered);
+	}
+
+	#[benchmark]
+	fn proposal_create() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		let carrier = funded_account::<T>("carrier", 0);
+
+		#[extrinsic_call]
+		proposal_create(
+			RawOrigin::Signed(carrier.clone()),
+			client.clone(),
+			PACKAGE_ID,
+			1_000u32.into(),
+			100u32.into(),
+			10,
+			10u32.into(),
+		);
+
+		assert!(T::Proposals::proposal(&client, &PACKAGE_ID, &carrier).is_some());
+	}
+
+	#[benchmark]
+	fn package_create() {
+		let client = funded_account::<T>("client", 0);
+		let description =
+			vec![0u8; <T as pallet_package::Config>::DescriptionMaxLength::get() as usize];
+
+		// Deriving the package ID is the worst case
+		#[extrinsic_call]
+		package_create(
+			RawOrigin::Signed(client.clone()),
+			None,
+			vec![0u8; 256],
+			vec![0u8; 256],
+			description,
+			100,
+			100,
+			100,
+			100,
+			true,
+			true,
+			100u32.into(),
+		);
+
+		let packages = T::Packages::packages_of(&client, 0, 2);
+		assert_eq!(packages.len(), 1);
+		assert_eq!(packages[0].1.status, PackageStatus::New);
+	}
+
+	#[benchmark]
+	fn package_collect() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		let carrier = propose::<T>(&client, PACKAGE_ID, 1);
+		LogisticsModule::<T>::package_assign(
+			RawOrigin::Signed(client.clone()).into(),
+			PACKAGE_ID,
+			carrier.clone(),
+			None,
+		)
+		.expect("proposal is valid; qed");
+
+		#[extrinsic_call]
+		package_collect(RawOrigin::Signed(carrier), PACKAGE_ID, client.clone());
+
+		assert_status::<T>(&client, PackageStatus::InTransit);
+	}
+
+	#[benchmark]
+	fn package_hold() {
+		let client = funded_account::<T>("client", 0);
+		let carrier = collected_package::<T>(&client, PACKAGE_ID, 1);
+
+		#[extrinsic_call]
+		package_hold(RawOrigin::Signed(carrier), PACKAGE_ID, client.clone());
+
+		assert_status::<T>(&client, PackageStatus::OnHold);
+	}
+
+	#[benchmark]
+	fn package_release() {
+		let client = funded_account::<T>("client", 0);
+		collected_package::<T>(&client, PACKAGE_ID, 1);
+		T::Packages::dispute_package(&client, &PACKAGE_ID).expect("package is in transit; qed");
+
+		#[extrinsic_call]
+		package_release(RawOrigin::Signed(client.clone()), PACKAGE_ID, client.clone());
+
+		assert_status::<T>(&client, PackageStatus::InTransit);
+	}
+
+	#[benchmark]
+	fn package_dispute() {
+		let client = funded_account::<T>("client", 0);
+		collected_package::<T>(&client, PACKAGE_ID, 1);
+
+		#[extrinsic_call]
+		package_dispute(RawOrigin::Signed(client.clone()), PACKAGE_ID);
+
+		assert_status::<T>(&client, PackageStatus::Disputed);
+	}
+
+	#[benchmark]
+	fn package_return() {
+		let client = funded_account::<T>("client", 0);
+		let carrier = collected_package::<T>(&client, PACKAGE_ID, 1);
+
+		#[extrinsic_call]
+		package_return(RawOrigin::Signed(carrier), PACKAGE_ID, client.clone());
+
+		assert_status::<T>(&client, PackageStatus::Returned);
+	}
+
+	#[benchmark]
+	fn package_report_lost() {
+		let client = funded_account::<T>("client", 0);
+		let carrier = collected_package::<T>(&client, PACKAGE_ID, 1);
+
+		#[extrinsic_call]
+		package_report_lost(RawOrigin::Signed(carrier), PACKAGE_ID, client.clone());
+
+		assert_status::<T>(&client, PackageStatus::Lost);
+	}
+
+	#[benchmark]
+	fn package_expire() {
+		let client = funded_account::<T>("client", 0);
+		let caller = funded_account::<T>("caller", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number() +
+				<T as pallet_package::Config>::PackageExpiry::get() +
+				1u32.into(),
+		);
+
+		#[extrinsic_call]
+		package_expire(RawOrigin::Signed(caller), client.clone(), PACKAGE_ID);
+
+		assert_status::<T>(&client, PackageStatus::Expired);
+	}
+
+	#[benchmark]
+	fn prune_concluded(
+		p: Linear<1, { <T as pallet_carrier::Config>::MaxProposalsPerPackage::get() }>,
+	) {
+		let client = funded_account::<T>("client", 0);
+		let carrier = collected_package::<T>(&client, PACKAGE_ID, p);
+		deliver_package::<T>(&client, PACKAGE_ID, carrier);
+		let now = past_retention::<T>();
+
+		#[block]
+		{
+			LogisticsModule::<T>::prune_next(now);
+		}
+
+		assert!(T::Packages::package(&client, &PACKAGE_ID).is_none());
+	}
+
+	#[benchmark]
+	fn on_idle(c: Linear<0, { <T as pallet_package::Config>::MaxConcludedPackages::get() }>) {
+		let client = funded_account::<T>("client", 0);
+		for index in 0..c {
+			let carrier = collected_package::<T>(&client, package_id(index), 1);
+			deliver_package::<T>(&client, package_id(index), carrier);
+		}
+		let now = past_retention::<T>();
+
+		#[block]
+		{
+			LogisticsModule::<T>::on_idle(now, Weight::MAX);
+		}
+
+		assert_eq!(pallet_package::ConcludedHead::<T>::get(), c as u64);
+	}
+
+	#[benchmark]
+	fn set_parameter() -> Result<(), BenchmarkError> {
+		let origin =
+			T::ParametersOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let ernest_deposit = <T as Config>::MaximumParameters::get().ernest_deposit;
+
+		#[extrinsic_call]
+		set_parameter(
+			origin as T::RuntimeOrigin,
+			MarketplaceParameter::ErnestDeposit(ernest_deposit),
+		);
+
+		assert_eq!(Parameters::<T>::get().ernest_deposit, ernest_deposit);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn pause() -> Result<(), BenchmarkError> {
+		let origin =
+			T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let (pallet_name, call_name) = pause_target::<T>();
+
+		#[extrinsic_call]
+		pause(origin as T::RuntimeOrigin, pallet_name.clone(), call_name.clone());
+
+		assert!(PausedCalls::<T>::contains_key(&pallet_name, &call_name));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn unpause() -> Result<(), BenchmarkError> {
+		let origin =
+			T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let (pallet_name, call_name) = pause_target::<T>();
+		PausedCalls::<T>::insert(&pallet_name, &call_name, ());
+
+		#[extrinsic_call]
+		unpause(origin as T::RuntimeOrigin, pallet_name.clone(), call_name.clone());
+
+		assert!(!PausedCalls::<T>::contains_key(&pallet_name, &call_name));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn proposal_withdraw() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		let carrier = propose::<T>(&client, PACKAGE_ID, 1);
+
+		#[extrinsic_call]
+		proposal_withdraw(RawOrigin::Signed(carrier.clone()), client.clone(), PACKAGE_ID);
+
+		assert!(T::Proposals::proposal(&client, &PACKAGE_ID, &carrier).is_none());
+	}
+
+	#[benchmark]
+	fn package_set_metadata() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		tag_package::<T>(&client, PACKAGE_ID);
+		let key = metadata_key::<T>(0);
+
+		#[extrinsic_call]
+		package_set_metadata(
+			RawOrigin::Signed(client.clone()),
+			PACKAGE_ID,
+			key.clone(),
+			metadata_value::<T>(),
+		);
+
+		assert!(T::Packages::metadata(&client, &PACKAGE_ID)
+			.contains_key(&key.try_into().expect("key is within bounds; qed")));
+	}
+
+	#[benchmark]
+	fn package_clear_metadata() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		tag_package::<T>(&client, PACKAGE_ID);
+		let key = metadata_key::<T>(1);
+
+		#[extrinsic_call]
+		package_clear_metadata(RawOrigin::Signed(client.clone()), PACKAGE_ID, key.clone());
+
+		assert!(!T::Packages::metadata(&client, &PACKAGE_ID)
+			.contains_key(&key.try_into().expect("key is within bounds; qed")));
+	}
+
+	#[benchmark]
+	fn package_set_reference() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		tag_package::<T>(&client, PACKAGE_ID);
+		let reference =
+			vec![2u8; <T as pallet_package::Config>::MaxReferenceLength::get() as usize];
+
+		// Replacing an existing reference is the worst case
+		#[extrinsic_call]
+		package_set_reference(
+			RawOrigin::Signed(client.clone()),
+			PACKAGE_ID,
+			Some(reference.clone()),
+		);
+
+		assert_eq!(T::Packages::package_by_reference(&client, &reference), Some(PACKAGE_ID));
+	}
+
+	#[benchmark]
+	fn package_set_line_items(
+		l: Linear<0, { <T as pallet_package::Config>::MaxLineItems::get() }>,
+	) {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+
+		#[extrinsic_call]
+		package_set_line_items(RawOrigin::Signed(client.clone()), PACKAGE_ID, line_items::<T>(l));
+
+		assert_eq!(T::Packages::line_items(&client, &PACKAGE_ID).len() as u32, l);
+	}
+
+	#[benchmark]
+	fn package_deliver_partial(
+		l: Linear<1, { <T as pallet_package::Config>::MaxLineItems::get() }>,
+	) {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		itemise_package::<T>(&client, PACKAGE_ID, l);
+		let carrier = insure_and_collect::<T>(&client, PACKAGE_ID, 1);
+
+		charge_commission::<T>();
+
+		// Late delivery, so penalties are deducted from the fee
+		frame_system::Pallet::<T>::set_block_number(100u32.into());
+
+		#[extrinsic_call]
+		package_deliver_partial(
+			RawOrigin::Signed(client.clone()),
+			PACKAGE_ID,
+			carrier,
+			vec![5; l as usize],
+		);
+
+		assert_status::<T>(&client, PackageStatus::Delivered);
+	}
+
+	#[benchmark]
+	fn claim_missing_items() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		itemise_package::<T>(&client, PACKAGE_ID, 1);
+		let carrier = insure_and_collect::<T>(&client, PACKAGE_ID, 1);
+		LogisticsModule::<T>::package_deliver_partial(
+			RawOrigin::Signed(client.clone()).into(),
+			PACKAGE_ID,
+			carrier,
+			vec![0],
+		)
+		.expect("package is in transit; qed");
+
+		#[extrinsic_call]
+		claim_missing_items(RawOrigin::Signed(client.clone()), PACKAGE_ID);
+
+		assert!(pallet_insurance::Pallet::<T>::get_claim(&client, &PACKAGE_ID).is_some());
+	}
+
+	#[benchmark]
+	fn package_update(
+		p: Linear<0, { <T as pallet_carrier::Config>::MaxProposalsPerPackage::get() }>,
+	) {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		itemise_package::<T>(
+			&client,
+			PACKAGE_ID,
+			<T as pallet_package::Config>::MaxLineItems::get(),
+		);
+		let carrier = propose::<T>(&client, PACKAGE_ID, p);
+		let description =
+			vec![0u8; <T as pallet_package::Config>::DescriptionMaxLength::get() as usize];
+
+		#[extrinsic_call]
+		package_update(
+			RawOrigin::Signed(client.clone()),
+			PACKAGE_ID,
+			vec![1u8; 256],
+			vec![1u8; 256],
+			description,
+			100,
+			100,
+			100,
+			100,
+			true,
+			true,
+			100u32.into(),
+		);
+
+		assert_status::<T>(&client, PackageStatus::New);
+		assert!(p == 0 || !T::Proposals::proposal_is_valid(&client, &PACKAGE_ID, &carrier));
+	}
+
+	#[benchmark]
+	fn proposal_confirm() {
+		let client = funded_account::<T>("client", 0);
+		create_package::<T>(&client, PACKAGE_ID);
+		let carrier = propose::<T>(&client, PACKAGE_ID, 1);
+		T::Proposals::mark_proposals_stale(&client, &PACKAGE_ID);
+
+		#[extrinsic_call]
+		proposal_confirm(RawOrigin::Signed(carrier.clone()), client.clone(), PACKAGE_ID);
+
+		assert!(T::Proposals::proposal_is_valid(&client, &PACKAGE_ID, &carrier));
+	}
+
+	impl_benchmark_test_suite!(LogisticsModule, crate::mock::new_test_ext(), crate::mock::Test);
+}
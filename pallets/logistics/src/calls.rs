@@ -3,84 +3,85 @@ use frame_support::pallet_macros::*;
 #[pallet_section]
 mod calls {
 
-	use frame_support::{sp_runtime::SaturatedConversion, traits::ExistenceRequirement};
-	use frame_system;
+	use frame_support::sp_runtime::{traits::Zero, Perbill};
+	use logistics_primitives::{FeeTerms, PackageAction, PackageStatus};
+	use pallet_package::types::{Dimensions, LineItem, LineItemParams, Manifest};
 	use sp_std::vec::Vec;
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(10)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(<T as Config>::WeightInfo::package_assign(
+			<T as pallet_carrier::Config>::MaxProposalsPerPackage::get()
+		))]
 		pub fn package_assign(
 			origin: OriginFor<T>,
 			package_id: PackageId,
 			carrier: T::AccountId,
+			insured_value: Option<pallet_insurance::BalanceOf<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
 			// Ensure package exists and is still "New"
-			ensure!(
-				pallet_package::Pallet::<T>::package_is_new(&who, &package_id),
-				pallet_package::Error::<T>::InvalidPackage
-			);
+			T::Packages::ensure_can_transition(&who, &package_id, PackageAction::Assign)?;
 
 			// Ensure that proposal exists and is in a valid state to be assigned
 			ensure!(
-				pallet_carrier::Pallet::<T>::proposal_is_valid(&who, &package_id, &carrier),
+				T::Proposals::proposal_is_valid(&who, &package_id, &carrier),
 				pallet_carrier::Error::<T>::InvalidProposal
 			);
 
 			// Reserve Maximum fee amount
-			let max_fee: BalanceOf<T> = pallet_carrier::Pallet::<T>::proposal_maximum_fee_amount(
-				&who,
-				&package_id,
-				&carrier,
-			)
-			.unwrap_or(0)
-			.try_into()
-			.map_err(|_| Error::<T>::InvalidFee)?;
+			let max_fee = Self::reserved_fee(&who, &package_id, &carrier)?;
 
 			<T as pallet::Config>::Currency::reserve(&who, max_fee)
 				.map_err(|_| Error::<T>::InsufficientFunds)?;
 
 			// Update package status and carrier
-			pallet_package::Pallet::<T>::assign_package(&who, &package_id, &carrier)?;
+			T::Packages::assign_package(&who, &package_id, &carrier)?;
+
+			// Accept carrier's proposal, rejecting all others
+			T::Proposals::accept_proposal(&who, &package_id, &carrier)?;
 
-			// Reject other proposals
-			pallet_carrier::Pallet::<T>::reject_proposals(&who, &package_id, &carrier);
+			// Optionally buy cover from the insurance pool
+			if let Some(insured_value) = insured_value {
+				let manifest = T::Packages::manifest(&who, &package_id)
+					.ok_or(pallet_package::Error::<T>::PackageDoesNotExist)?;
+				ensure!(
+					insured_value <= manifest.declared_value,
+					Error::<T>::InsuredValueExceedsDeclaredValue
+				);
 
-			// Accept carrier's proposal
-			pallet_carrier::Pallet::<T>::accept_proposal(&who, &package_id, &carrier)?;
+				pallet_insurance::Pallet::<T>::purchase_cover(
+					&who,
+					&package_id,
+					&carrier,
+					insured_value,
+					manifest.hazardous_materials,
+					manifest.climate_controlled,
+				)?;
+			}
 
 			Ok(())
 		}
 
 		#[pallet::call_index(20)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(<T as Config>::WeightInfo::package_cancel())]
 		pub fn package_cancel(origin: OriginFor<T>, package_id: PackageId) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			// Ensure package exists and is still "New"
-			// We can't cancel a package that's already been assigned
-			ensure!(
-				pallet_package::Pallet::<T>::package_is_new(&who, &package_id),
-				pallet_package::Error::<T>::InvalidPackage
-			);
-
 			// Cancel Package
-			pallet_package::Pallet::<T>::cancel_package(&who, &package_id)?;
+			// We can't cancel a package that's already been assigned
+			T::Packages::cancel_package(&who, &package_id)?;
 
 			// Release reserved ernest reserve
-			<T as pallet::Config>::Currency::unreserve(
-				&who,
-				<T as pallet::Config>::ErnestDeposit::get(),
-			);
+			T::Packages::release_ernest_deposit(&who, &package_id);
 
 			Ok(())
 		}
 
 		#[pallet::call_index(30)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(<T as Config>::WeightInfo::package_deliver())]
 		pub fn package_deliver(
 			origin: OriginFor<T>,
 			package_id: PackageId,
@@ -88,93 +89,384 @@ mod calls {
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?; // Only client can mark as delivered
 
-			// Ensure package exists and is still "InTransit"
-			ensure!(
-				pallet_package::Pallet::<T>::package_is_in_transit(&who, &package_id),
-				pallet_package::Error::<T>::InvalidPackage
-			);
+			Self::settle_delivery(&who, &package_id, &carrier, Perbill::one())
+		}
 
-			// Mark package as delivered
-			pallet_package::Pallet::<T>::deliver_package(&who, &package_id)?;
+		#[pallet::call_index(50)]
+		#[pallet::weight(<T as Config>::WeightInfo::proposal_create())]
+		pub fn proposal_create(
+			origin: OriginFor<T>,
+			client: T::AccountId,
+			package_id: PackageId,
+			maximum_fee_amount: BalanceOf<T>,
+			minimum_fee_amount: BalanceOf<T>,
+			penalty_period: u32,
+			penalty_amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			let package = pallet_package::Pallet::<T>::get_package(&who, &package_id)
-				.ok_or(pallet_package::Error::<T>::PackageDoesNotExist)?;
+			// Carriers can only bid on packages still waiting to be assigned
+			T::Packages::ensure_can_transition(&client, &package_id, PackageAction::Assign)?;
 
-			// Pay carrier
-			let final_fee = pallet_carrier::Pallet::<T>::calculate_final_fee_amount(
-				&who,
-				&package_id,
-				&carrier,
-				package.collected_on.unwrap_or(<frame_system::Pallet<T>>::block_number()),
-				<frame_system::Pallet<T>>::block_number(),
+			let terms =
+				FeeTerms { maximum_fee_amount, minimum_fee_amount, penalty_period, penalty_amount };
+
+			T::Proposals::create_proposal(&who, &client, &package_id, terms)
+		}
+
+		#[pallet::call_index(70)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_create())]
+		pub fn package_create(
+			origin: OriginFor<T>,
+			package_id: Option<PackageId>,
+			pickup: Vec<u8>,
+			destination: Vec<u8>,
+			description: Vec<u8>,
+			length: u32,
+			width: u32,
+			height: u32,
+			weight: u32,
+			contains_hazardous_materials: bool,
+			requires_climate_controlled: bool,
+			declared_value: pallet_package::BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let manifest = Manifest::new(
+				description,
+				Dimensions { length, width, height },
+				weight,
+				contains_hazardous_materials,
+				requires_climate_controlled,
+				declared_value,
 			)?;
 
-			// Unreserve Maximum fee amount
-			let max_fee: BalanceOf<T> = pallet_carrier::Pallet::<T>::proposal_maximum_fee_amount(
-				&who,
-				&package_id,
-				&carrier,
-			)
-			.unwrap_or(0)
-			.try_into()
-			.map_err(|_| Error::<T>::InvalidFee)?;
+			// Without an ID of their own, the client is given one which is unique across all
+			// clients
+			T::Packages::create_package(&who, package_id, pickup, destination, manifest)?;
 
-			<T as pallet::Config>::Currency::unreserve(&who, max_fee);
+			Ok(())
+		}
+
+		#[pallet::call_index(80)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_collect())]
+		pub fn package_collect(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			client: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?; // Only the assigned carrier can collect a package
+
+			T::Packages::collect_package(&client, &package_id, &who)
+		}
+
+		#[pallet::call_index(90)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_hold())]
+		pub fn package_hold(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			client: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?; // Only the assigned carrier can hold a package
+
+			T::Packages::hold_package(&client, &package_id, &who)
+		}
+
+		#[pallet::call_index(100)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_release())]
+		pub fn package_release(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			client: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let package =
+				T::Packages::ensure_can_transition(&client, &package_id, PackageAction::Release)?;
+
+			// Holds are released by the carrier, disputes are withdrawn by the client
+			if package.status == PackageStatus::Disputed {
+				ensure!(who == client, pallet_package::Error::<T>::InvalidPackage);
+			} else {
+				ensure!(
+					package.carrier == Some(who.clone()),
+					pallet_package::Error::<T>::InvalidCarrier
+				);
+			}
+
+			T::Packages::release_package(&client, &package_id, &who)
+		}
+
+		#[pallet::call_index(110)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_dispute())]
+		pub fn package_dispute(origin: OriginFor<T>, package_id: PackageId) -> DispatchResult {
+			let who = ensure_signed(origin)?; // Only client can dispute
+
+			T::Packages::dispute_package(&who, &package_id)
+		}
+
+		#[pallet::call_index(120)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_return())]
+		pub fn package_return(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			client: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?; // Only the assigned carrier can return a package
+
+			// Mark package as returned to sender
+			T::Packages::return_package(&client, &package_id, &who)?;
+
+			// Carrier is paid their minimum fee for the return journey
+			let minimum_fee = T::Proposals::proposal_minimum_fee_amount(&client, &package_id, &who)
+				.ok_or(pallet_carrier::Error::<T>::ProposalDoesNotExist)?;
+
+			Self::release_reserves(&client, &package_id, &who)?;
 
-			// Transfer final fee amount to carrier
 			<T as pallet::Config>::Currency::transfer(
+				&client,
 				&who,
-				&carrier,
-				final_fee.saturated_into(),
+				minimum_fee,
 				ExistenceRequirement::KeepAlive,
 			)?;
+			SettledFees::<T>::insert(&client, &package_id, minimum_fee);
 
-			// Unreserve ernest deposit
-			<T as pallet::Config>::Currency::unreserve(
-				&who,
-				<T as pallet::Config>::ErnestDeposit::get(),
+			pallet_insurance::Pallet::<T>::conclude_cover(&client, &package_id);
+
+			Ok(())
+		}
+
+		#[pallet::call_index(130)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_report_lost())]
+		pub fn package_report_lost(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			client: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?; // Only the assigned carrier can report a loss
+
+			// Mark package as lost
+			T::Packages::report_package_lost(&client, &package_id, &who)?;
+
+			// Carrier is not paid for a lost package
+			Self::release_reserves(&client, &package_id, &who)?;
+
+			// Client may now claim against any cover bought on the package
+			pallet_insurance::Pallet::<T>::conclude_cover(&client, &package_id);
+
+			Ok(())
+		}
+
+		#[pallet::call_index(140)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_expire())]
+		pub fn package_expire(
+			origin: OriginFor<T>,
+			client: T::AccountId,
+			package_id: PackageId,
+		) -> DispatchResult {
+			// Anyone can expire a package which has waited too long for a carrier
+			let who = ensure_signed(origin)?;
+
+			T::Packages::expire_package(&client, &package_id, &who)?;
+
+			// Release reserved ernest reserve
+			T::Packages::release_ernest_deposit(&client, &package_id);
+
+			Ok(())
+		}
+
+		#[pallet::call_index(150)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_parameter())]
+		pub fn set_parameter(
+			origin: OriginFor<T>,
+			parameter: MarketplaceParameterOf<T>,
+		) -> DispatchResult {
+			T::ParametersOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				parameter.is_within(
+					&<T as Config>::MinimumParameters::get(),
+					&<T as Config>::MaximumParameters::get()
+				),
+				Error::<T>::ParameterOutOfBounds
 			);
 
-			Self::deposit_event(Event::PackageDelivered { client: who, package: package_id });
+			// Deposits already reserved are recorded with their package or proposal,
+			// so a new amount only applies to packages and proposals made from now on
+			Parameters::<T>::mutate(|parameters| parameters.set(parameter.clone()));
+
+			Self::deposit_event(Event::ParameterSet { parameter });
 
 			Ok(())
 		}
 
-		#[pallet::call_index(50)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn proposal_create(
+		/// Halt a call of one of the marketplace pallets, or every call of the pallet
+		/// if no call is named. Calls in the runtime's safe list remain available.
+		#[pallet::call_index(160)]
+		#[pallet::weight(<T as Config>::WeightInfo::pause())]
+		pub fn pause(
+			origin: OriginFor<T>,
+			pallet_name: Name,
+			call_name: Option<Name>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			Self::ensure_pausable(&pallet_name, &call_name)?;
+			ensure!(
+				!PausedCalls::<T>::contains_key(&pallet_name, &call_name),
+				Error::<T>::AlreadyPaused
+			);
+
+			PausedCalls::<T>::insert(&pallet_name, &call_name, ());
+
+			Self::deposit_event(Event::Paused { pallet_name, call_name });
+
+			Ok(())
+		}
+
+		/// Resume a call or pallet halted by `pause`
+		#[pallet::call_index(170)]
+		#[pallet::weight(<T as Config>::WeightInfo::unpause())]
+		pub fn unpause(
+			origin: OriginFor<T>,
+			pallet_name: Name,
+			call_name: Option<Name>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				PausedCalls::<T>::contains_key(&pallet_name, &call_name),
+				Error::<T>::NotPaused
+			);
+
+			PausedCalls::<T>::remove(&pallet_name, &call_name);
+
+			Self::deposit_event(Event::Unpaused { pallet_name, call_name });
+
+			Ok(())
+		}
+
+		/// Withdraw an open proposal, returning its deposit to the carrier
+		#[pallet::call_index(180)]
+		#[pallet::weight(<T as Config>::WeightInfo::proposal_withdraw())]
+		pub fn proposal_withdraw(
 			origin: OriginFor<T>,
 			client: T::AccountId,
 			package_id: PackageId,
-			maximum_fee_amount: u128,
-			minimum_fee_amount: u128,
-			penalty_period: u32,
-			penalty_amount: u128,
-		) -> DispatchResult {
-			pallet_carrier::Pallet::<T>::proposal_create(
-				origin,
-				client,
-				package_id,
-				maximum_fee_amount,
-				minimum_fee_amount,
-				penalty_period,
-				penalty_amount,
-			)
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?; // Only the carrier who made the proposal
+
+			T::Proposals::withdraw_proposal(&client, &package_id, &who)
 		}
 
-		#[pallet::call_index(60)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn proposal_accept(
+		/// Tag a package with a key/value entry, such as a purchase order or cost centre.
+		/// A deposit is reserved for every byte stored until the package is pruned.
+		#[pallet::call_index(190)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_set_metadata())]
+		pub fn package_set_metadata(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			key: Vec<u8>,
+			value: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?; // Only the client can tag their packages
+
+			T::Packages::set_metadata(&who, &package_id, key, value)
+		}
+
+		#[pallet::call_index(200)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_clear_metadata())]
+		pub fn package_clear_metadata(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			key: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			T::Packages::clear_metadata(&who, &package_id, key)
+		}
+
+		/// Set or remove the reference the client's own systems know a package by,
+		/// such as an ERP order number. References are unique among the client's packages.
+		#[pallet::call_index(210)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_set_reference())]
+		pub fn package_set_reference(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			reference: Option<Vec<u8>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			T::Packages::set_external_reference(&who, &package_id, reference)
+		}
+
+		/// Replace the line items of a package shipping a mix of items.
+		/// Only permitted until the package is assigned to a carrier.
+		#[pallet::call_index(220)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_set_line_items(
+			<T as pallet_package::Config>::MaxLineItems::get()
+		))]
+		pub fn package_set_line_items(
+			origin: OriginFor<T>,
+			package_id: PackageId,
+			line_items: Vec<LineItemParams<pallet_package::BalanceOf<T>>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let line_items =
+				line_items.into_iter().map(LineItem::new).collect::<Result<Vec<_>, _>>()?;
+
+			T::Packages::set_line_items(&who, &package_id, line_items)
+		}
+
+		/// Mark a package as delivered with only some of its line items, paying the carrier
+		/// the share of the fee matching the share of the declared value received
+		#[pallet::call_index(230)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_deliver_partial(
+			<T as pallet_package::Config>::MaxLineItems::get()
+		))]
+		pub fn package_deliver_partial(
 			origin: OriginFor<T>,
 			package_id: PackageId,
 			carrier: T::AccountId,
+			received: Vec<u32>,
 		) -> DispatchResult {
-			pallet_carrier::Pallet::<T>::proposal_accept(origin, package_id, carrier)
+			let who = ensure_signed(origin)?; // Only client can mark as delivered
+
+			let share = T::Packages::confirm_received(&who, &package_id, received)?;
+
+			Self::settle_delivery(&who, &package_id, &carrier, share)
 		}
 
-		#[pallet::call_index(70)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn package_create(
+		/// Claim the declared value of line items which did not arrive from the package's
+		/// insurance cover, up to the insured value
+		#[pallet::call_index(240)]
+		#[pallet::weight(<T as Config>::WeightInfo::claim_missing_items())]
+		pub fn claim_missing_items(origin: OriginFor<T>, package_id: PackageId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let missing = T::Packages::missing_value(&who, &package_id)
+				.ok_or(pallet_package::Error::<T>::NoLineItems)?;
+			ensure!(!missing.is_zero(), Error::<T>::NothingMissing);
+
+			let policy = pallet_insurance::Pallet::<T>::get_policy(&who, &package_id)
+				.ok_or(pallet_insurance::Error::<T>::PolicyDoesNotExist)?;
+
+			pallet_insurance::Pallet::<T>::file_claim(
+				&who,
+				&package_id,
+				policy.insured_value.min(missing),
+			)
+		}
+
+		/// Amend the addresses and manifest of a package which is still waiting for a carrier.
+		/// Open proposals are marked stale and must be confirmed by their carriers before the
+		/// client can accept them.
+		#[pallet::call_index(250)]
+		#[pallet::weight(<T as Config>::WeightInfo::package_update(
+			<T as pallet_carrier::Config>::MaxProposalsPerPackage::get()
+		))]
+		pub fn package_update(
 			origin: OriginFor<T>,
 			package_id: PackageId,
 			pickup: Vec<u8>,
@@ -186,30 +478,38 @@ mod calls {
 			weight: u32,
 			contains_hazardous_materials: bool,
 			requires_climate_controlled: bool,
+			declared_value: pallet_package::BalanceOf<T>,
 		) -> DispatchResult {
-			pallet_package::Pallet::<T>::package_create(
-				origin,
-				package_id,
-				pickup,
-				destination,
+			let who = ensure_signed(origin)?;
+
+			let manifest = Manifest::new(
 				description,
-				length,
-				width,
-				height,
+				Dimensions { length, width, height },
 				weight,
 				contains_hazardous_materials,
 				requires_climate_controlled,
-			)
+				declared_value,
+			)?;
+
+			T::Packages::update_package(&who, &package_id, pickup, destination, manifest)?;
+
+			// Carriers quoted for the package as it was
+			T::Proposals::mark_proposals_stale(&who, &package_id);
+
+			Ok(())
 		}
 
-		#[pallet::call_index(80)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn package_collect(
+		/// Confirm a proposal made stale by changes to the package still stands
+		#[pallet::call_index(260)]
+		#[pallet::weight(<T as Config>::WeightInfo::proposal_confirm())]
+		pub fn proposal_confirm(
 			origin: OriginFor<T>,
-			package_id: PackageId,
 			client: T::AccountId,
+			package_id: PackageId,
 		) -> DispatchResult {
-			pallet_package::Pallet::<T>::package_collect(origin, package_id, client)
+			let who = ensure_signed(origin)?; // Only the carrier who made the proposal
+
+			T::Proposals::confirm_proposal(&client, &package_id, &who)
 		}
 	}
 }
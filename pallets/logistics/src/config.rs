@@ -4,32 +4,72 @@ use frame_support::pallet_macros::*;
 mod config {
 
 	use frame_support::traits::{Currency, OnUnbalanced, ReservableCurrency};
+	use pallet_carrier::traits::ProposalMutate;
+	use pallet_package::traits::PackageMutate;
 
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 	type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
 		<T as frame_system::Config>::AccountId,
 	>>::NegativeImbalance;
 
+	/// Declared values, insured values and fees are all amounts of the same balance type
 	#[pallet::config]
 	pub trait Config:
-		frame_system::Config + pallet_carrier::Config + pallet_package::Config
+		frame_system::Config
+		+ pallet_carrier::Config
+		+ pallet_package::Config<
+			Currency: ReservableCurrency<
+				<Self as frame_system::Config>::AccountId,
+				Balance = pallet_carrier::BalanceOf<Self>,
+			>,
+		> + pallet_insurance::Config<
+			Currency: ReservableCurrency<
+				<Self as frame_system::Config>::AccountId,
+				Balance = pallet_carrier::BalanceOf<Self>,
+			>,
+		>
 	{
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
-		/// The currency trait.
-		type Currency: ReservableCurrency<Self::AccountId>;
-
-		/// Ernest deposit amount
-		#[pallet::constant]
-		type ErnestDeposit: Get<BalanceOf<Self>>;
+		/// The currency trait. Fees are paid in the same currency carriers propose them in.
+		type Currency: ReservableCurrency<
+			Self::AccountId,
+			Balance = pallet_carrier::BalanceOf<Self>,
+		>;
 
 		/// What to do with slashed funds.
 		type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
 
-		/// We don't want anyone storing the entirety of the Bee Movie script on-chain
+		/// What to do with the marketplace's commission on delivered packages.
+		type Commission: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Packages, as kept by the package pallet.
+		type Packages: PackageMutate<Self>;
+
+		/// Carrier proposals on packages, as kept by the carrier pallet.
+		type Proposals: ProposalMutate<Self>;
+
+		/// Origin which can change the marketplace parameters
+		type ParametersOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Marketplace parameters in effect until governance changes them
 		#[pallet::constant]
-		type DescriptionMaxLength: Get<u32>;
+		type DefaultParameters: Get<MarketplaceParametersOf<Self>>;
+
+		/// Lowest value governance can set each marketplace parameter to
+		#[pallet::constant]
+		type MinimumParameters: Get<MarketplaceParametersOf<Self>>;
+
+		/// Highest value governance can set each marketplace parameter to
+		#[pallet::constant]
+		type MaximumParameters: Get<MarketplaceParametersOf<Self>>;
+
+		/// Origin which can pause and unpause the marketplace's calls
+		type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 }
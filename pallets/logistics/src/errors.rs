@@ -6,7 +6,17 @@ mod errors {
 	pub enum Error<T> {
 		/// Not enough funds available to perform the requested action
 		InsufficientFunds,
-		/// Could not convert fee amount into valid balance
-		InvalidFee,
+		/// New parameter value lies outside of its permitted bounds
+		ParameterOutOfBounds,
+		/// Only calls of the marketplace pallets can be paused
+		NotPausable,
+		/// Call or pallet is already paused
+		AlreadyPaused,
+		/// Call or pallet is not paused
+		NotPaused,
+		/// Every line item of the package arrived, so there is nothing to claim
+		NothingMissing,
+		/// Packages cannot be insured for more than their declared value
+		InsuredValueExceedsDeclaredValue,
 	}
 }
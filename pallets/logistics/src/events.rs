@@ -7,15 +7,13 @@ mod events {
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// New package created [Client ID, Package ID]
-		PackageCreated { client: T::AccountId, package: PackageId },
-		/// Package has been assigned to a carrier [Client ID, Package ID, Carrier ID]
-		PackageAssigned { client: T::AccountId, package: PackageId, carrier: T::AccountId },
-		/// Package collected by carrier [Client ID, Package ID, Carrier ID]
-		PackageCollected { client: T::AccountId, package: PackageId, carrier: T::AccountId },
-		/// Package has been delivered [Client ID, Package ID]
-		PackageDelivered { client: T::AccountId, package: PackageId },
-		/// Package has been cancelled [Client ID, Package ID]
-		PackageCancelled { client: T::AccountId, package: PackageId },
+		/// Package has been delivered [Client ID, Package ID, Commission kept by the marketplace]
+		PackageDelivered { client: T::AccountId, package: PackageId, commission: BalanceOf<T> },
+		/// Marketplace parameter changed by governance [New value]
+		ParameterSet { parameter: MarketplaceParameterOf<T> },
+		/// Calls halted, every call of the pallet if no call is named [Pallet, Call]
+		Paused { pallet_name: Name, call_name: Option<Name> },
+		/// Halted calls resumed [Pallet, Call]
+		Unpaused { pallet_name: Name, call_name: Option<Name> },
 	}
 }
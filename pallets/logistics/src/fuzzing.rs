@@ -0,0 +1,391 @@
+//! Stateful harness for property-based and fuzz testing of the logistics marketplace.
+//!
+//! A sequence of [`Action`]s is applied to the mock runtime and the marketplace's
+//! invariants are checked after every step. Failing calls are expected and ignored:
+//! every call is dispatched through the runtime, which rolls back a failed call's changes.
+//!
+//! The harness runs under proptest with `cargo test`, and under libFuzzer with
+//! `cargo fuzz run marketplace` from `pallets/logistics`.
+
+use crate::mock::*;
+use frame_support::{traits::Hooks, weights::Weight};
+use pallet_carrier::types::ProposalStatus;
+use pallet_package::types::{PackageId, PackageStatus};
+use sp_runtime::traits::Dispatchable;
+use sp_std::collections::btree_map::BTreeMap;
+
+/// Accounts acting as clients and carriers. Any account may take either role.
+pub const ACCOUNTS: [u64; 5] = [CLIENT, CARRIER, OTHER_CARRIER, THIRD_CARRIER, NOBODY];
+
+/// Number of distinct package IDs each client can use
+pub const PACKAGES: u8 = 4;
+
+/// Number of distinct metadata keys, one more than a package can hold
+pub const METADATA_KEYS: u8 = 5;
+
+/// Number of distinct external references each client can use
+pub const REFERENCES: u8 = 3;
+
+/// A single call made against the marketplace.
+/// Accounts and packages are picked by index so that any input is meaningful.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
+pub enum Action {
+	PackageCreate {
+		client: u8,
+		package: u8,
+	},
+	ProposalCreate {
+		carrier: u8,
+		client: u8,
+		package: u8,
+		maximum_fee: u16,
+		minimum_fee: u16,
+		penalty_period: u8,
+		penalty_amount: u16,
+	},
+	ProposalWithdraw {
+		carrier: u8,
+		client: u8,
+		package: u8,
+	},
+	PackageAssign {
+		client: u8,
+		package: u8,
+		carrier: u8,
+	},
+	PackageCollect {
+		carrier: u8,
+		client: u8,
+		package: u8,
+	},
+	PackageDeliver {
+		client: u8,
+		package: u8,
+		carrier: u8,
+	},
+	PackageCancel {
+		client: u8,
+		package: u8,
+	},
+	PackageSetMetadata {
+		client: u8,
+		package: u8,
+		key: u8,
+		value_length: u8,
+	},
+	PackageSetReference {
+		client: u8,
+		package: u8,
+		reference: Option<u8>,
+	},
+	PackageUpdate {
+		client: u8,
+		package: u8,
+		declared_value: u16,
+	},
+	ProposalConfirm {
+		carrier: u8,
+		client: u8,
+		package: u8,
+	},
+	/// Move the chain forward, giving `on_idle` the chance to prune concluded packages
+	AdvanceBlocks {
+		blocks: u8,
+	},
+}
+
+fn account(index: u8) -> u64 {
+	ACCOUNTS[index as usize % ACCOUNTS.len()]
+}
+
+fn package_id(index: u8) -> PackageId {
+	[index % PACKAGES; 32]
+}
+
+/// Apply every action in turn, checking the invariants after each one
+pub fn run(actions: &[Action]) {
+	new_test_ext().execute_with(|| {
+		let issuance = Balances::total_issuance();
+
+		for action in actions {
+			apply(action);
+			check_invariants(issuance);
+		}
+	});
+}
+
+/// Dispatch a single action, ignoring whether it succeeded.
+/// Calls go through the runtime's dispatch, so a failed call is rolled back as it is on chain.
+pub fn apply(action: &Action) {
+	use crate::Call;
+
+	let (caller, call) = match *action {
+		Action::PackageCreate { client, package } => (
+			client,
+			Call::package_create {
+				package_id: Some(package_id(package)),
+				pickup: b"filled.count.soap".to_vec(),
+				destination: b"index.home.raft".to_vec(),
+				description: b"Books".to_vec(),
+				length: 10,
+				width: 20,
+				height: 30,
+				weight: 5,
+				contains_hazardous_materials: false,
+				requires_climate_controlled: false,
+				declared_value: 500,
+			},
+		),
+		Action::ProposalCreate {
+			carrier,
+			client,
+			package,
+			maximum_fee,
+			minimum_fee,
+			penalty_period,
+			penalty_amount,
+		} => (
+			carrier,
+			Call::proposal_create {
+				client: account(client),
+				package_id: package_id(package),
+				maximum_fee_amount: maximum_fee.into(),
+				minimum_fee_amount: minimum_fee.into(),
+				penalty_period: penalty_period.into(),
+				penalty_amount: penalty_amount.into(),
+			},
+		),
+		Action::ProposalWithdraw { carrier, client, package } => (
+			carrier,
+			Call::proposal_withdraw { client: account(client), package_id: package_id(package) },
+		),
+		Action::PackageAssign { client, package, carrier } => (
+			client,
+			Call::package_assign {
+				package_id: package_id(package),
+				carrier: account(carrier),
+				insured_value: None,
+			},
+		),
+		Action::PackageCollect { carrier, client, package } => (
+			carrier,
+			Call::package_collect { package_id: package_id(package), client: account(client) },
+		),
+		Action::PackageDeliver { client, package, carrier } => (
+			client,
+			Call::package_deliver { package_id: package_id(package), carrier: account(carrier) },
+		),
+		Action::PackageCancel { client, package } =>
+			(client, Call::package_cancel { package_id: package_id(package) }),
+		Action::PackageSetMetadata { client, package, key, value_length } => (
+			client,
+			Call::package_set_metadata {
+				package_id: package_id(package),
+				key: vec![b'a' + key % METADATA_KEYS],
+				value: vec![b'v'; value_length as usize],
+			},
+		),
+		Action::PackageSetReference { client, package, reference } => (
+			client,
+			Call::package_set_reference {
+				package_id: package_id(package),
+				reference: reference.map(|reference| vec![b'a' + reference % REFERENCES; 4]),
+			},
+		),
+		Action::PackageUpdate { client, package, declared_value } => (
+			client,
+			Call::package_update {
+				package_id: package_id(package),
+				pickup: b"filled.count.soap".to_vec(),
+				destination: b"index.home.raft".to_vec(),
+				description: b"Books".to_vec(),
+				length: 10,
+				width: 20,
+				height: 30,
+				weight: 5,
+				contains_hazardous_materials: false,
+				requires_climate_controlled: false,
+				declared_value: declared_value.into(),
+			},
+		),
+		Action::ProposalConfirm { carrier, client, package } => (
+			carrier,
+			Call::proposal_confirm { client: account(client), package_id: package_id(package) },
+		),
+		Action::AdvanceBlocks { blocks } => {
+			let now = System::block_number() + u64::from(blocks);
+			System::set_block_number(now);
+			LogisticsModule::on_idle(now, Weight::MAX);
+			return
+		},
+	};
+
+	let _ = RuntimeCall::LogisticsModule(call).dispatch(RuntimeOrigin::signed(account(caller)));
+}
+
+/// Panic if any of the marketplace's invariants does not hold
+pub fn check_invariants(issuance: u64) {
+	// Funds are only ever moved between accounts, never minted or burnt
+	assert_eq!(Balances::total_issuance(), issuance, "total issuance changed");
+	let held: u64 = frame_system::Account::<Test>::iter_values()
+		.map(|account| account.data.free + account.data.reserved)
+		.sum();
+	assert_eq!(held, issuance, "account balances do not add up to total issuance");
+
+	// Everything reserved is accounted for by an ernest deposit, a metadata deposit,
+	// a maximum fee or a proposal deposit
+	let mut expected = BTreeMap::<u64, u64>::new();
+
+	for (client, package_id, package) in pallet_package::Packages::<Test>::iter() {
+		*expected.entry(client).or_default() +=
+			pallet_package::MetadataDeposits::<Test>::get(client, package_id);
+
+		if package.status.is_concluded() {
+			continue
+		}
+
+		*expected.entry(client).or_default() +=
+			pallet_package::ErnestDeposits::<Test>::get(client, package_id);
+
+		if package.status != PackageStatus::New {
+			let carrier = package.carrier.expect("assigned packages have a carrier");
+			let proposal = CarrierModule::get_proposal((client, package_id, carrier))
+				.expect("assigned carrier has a proposal");
+			*expected.entry(client).or_default() += proposal.terms.maximum_fee_amount;
+		}
+	}
+
+	for proposal in pallet_carrier::Proposals::<Test>::iter_values() {
+		if proposal.status.is_open() {
+			*expected.entry(proposal.carrier).or_default() += proposal.deposit;
+		}
+	}
+
+	for account in ACCOUNTS {
+		assert_eq!(
+			Balances::reserved_balance(account),
+			expected.get(&account).copied().unwrap_or_default(),
+			"reserved balance of account {} is not backed by deposits and fees",
+			account
+		);
+	}
+
+	// A package has at most one accepted proposal, which is from its carrier
+	let mut accepted = BTreeMap::<(u64, PackageId), u64>::new();
+
+	for proposal in pallet_carrier::Proposals::<Test>::iter_values() {
+		if proposal.status != ProposalStatus::Accepted {
+			continue
+		}
+
+		let key = (proposal.client, proposal.package);
+		assert!(
+			accepted.insert(key, proposal.carrier).is_none(),
+			"package {:?} has more than one accepted proposal",
+			key
+		);
+	}
+
+	for (client, package_id, package) in pallet_package::Packages::<Test>::iter() {
+		assert_eq!(
+			package.carrier,
+			accepted.get(&(client, package_id)).copied(),
+			"carrier of package {:?} does not match its accepted proposal",
+			(client, package_id)
+		);
+	}
+
+	// The pallets' own consistency checks
+	LogisticsModule::do_try_state().expect("logistics state is consistent");
+	#[cfg(feature = "try-runtime")]
+	{
+		PackageModule::do_try_state().expect("package state is consistent");
+		CarrierModule::do_try_state().expect("carrier state is consistent");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+
+	fn index() -> impl Strategy<Value = u8> {
+		0..ACCOUNTS.len() as u8
+	}
+
+	fn package() -> impl Strategy<Value = u8> {
+		0..PACKAGES
+	}
+
+	fn action() -> impl Strategy<Value = Action> {
+		prop_oneof![
+			(index(), package())
+				.prop_map(|(client, package)| Action::PackageCreate { client, package }),
+			(index(), index(), package(), 0..1_200u16, 0..1_200u16, 0..20u8, 0..100u16).prop_map(
+				|(
+					carrier,
+					client,
+					package,
+					maximum_fee,
+					minimum_fee,
+					penalty_period,
+					penalty_amount,
+				)| Action::ProposalCreate {
+					carrier,
+					client,
+					package,
+					maximum_fee,
+					minimum_fee,
+					penalty_period,
+					penalty_amount,
+				}
+			),
+			(index(), index(), package()).prop_map(|(carrier, client, package)| {
+				Action::ProposalWithdraw { carrier, client, package }
+			}),
+			(index(), package(), index()).prop_map(|(client, package, carrier)| {
+				Action::PackageAssign { client, package, carrier }
+			}),
+			(index(), index(), package()).prop_map(|(carrier, client, package)| {
+				Action::PackageCollect { carrier, client, package }
+			}),
+			(index(), package(), index()).prop_map(|(client, package, carrier)| {
+				Action::PackageDeliver { client, package, carrier }
+			}),
+			(index(), package())
+				.prop_map(|(client, package)| Action::PackageCancel { client, package }),
+			(index(), package(), 0..METADATA_KEYS, 0..40u8).prop_map(
+				|(client, package, key, value_length)| Action::PackageSetMetadata {
+					client,
+					package,
+					key,
+					value_length
+				}
+			),
+			(index(), package(), proptest::option::of(0..REFERENCES)).prop_map(
+				|(client, package, reference)| Action::PackageSetReference {
+					client,
+					package,
+					reference
+				}
+			),
+			(index(), package(), 0..1_000u16).prop_map(|(client, package, declared_value)| {
+				Action::PackageUpdate { client, package, declared_value }
+			}),
+			(index(), index(), package()).prop_map(|(carrier, client, package)| {
+				Action::ProposalConfirm { carrier, client, package }
+			}),
+			(0..15u8).prop_map(|blocks| Action::AdvanceBlocks { blocks }),
+		]
+	}
+
+	proptest! {
+		#![proptest_config(ProptestConfig::with_cases(256))]
+
+		#[test]
+		fn marketplace_invariants_hold(actions in prop::collection::vec(action(), 1..64)) {
+			run(&actions);
+		}
+	}
+}
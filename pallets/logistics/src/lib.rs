@@ -2,6 +2,20 @@
 
 pub use pallet::*;
 
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
+
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzzing;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
 mod calls;
 mod config;
 mod errors;
@@ -9,7 +23,29 @@ mod events;
 pub mod types;
 
 use crate::types::*;
-use frame_support::pallet_macros::*;
+use frame_support::{
+	pallet_macros::*,
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{Saturating, Zero},
+		Perbill, Permill,
+	},
+	traits::{
+		CallMetadata, Contains, Currency, ExistenceRequirement, GetCallMetadata, GetCallName,
+		OnUnbalanced, PalletInfoAccess, ReservableCurrency, WithdrawReasons,
+	},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use logistics_primitives::MAX_PAGE_SIZE;
+use pallet_carrier::{
+	traits::{ProposalInspect, ProposalMutate},
+	types::Proposal,
+};
+use pallet_package::{
+	traits::{PackageInspect, PackageMutate},
+	types::{Package, PackageStatus},
+};
+use sp_std::{marker::PhantomData, vec::Vec};
 
 #[import_section(events::events)]
 #[import_section(errors::errors)]
@@ -21,26 +57,514 @@ pub mod pallet {
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	/// Fee paid to the carrier of each concluded package, kept until the package is archived
+	#[pallet::storage]
+	pub type SettledFees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		BalanceOf<T>,
+	>;
+
+	/// Marketplace parameters currently in effect
+	#[pallet::storage]
+	pub type Parameters<T: Config> =
+		StorageValue<_, MarketplaceParametersOf<T>, ValueQuery, T::DefaultParameters>;
+
+	/// Calls halted by `PauseOrigin`, with no call name when the whole pallet is paused
+	#[pallet::storage]
+	pub type PausedCalls<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Name, Blake2_128Concat, Option<Name>, ()>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-			// Remove all Delivered & Cancelled packages
-			// along with associated manifests and proposals
-			let concluded_packages =
-				pallet_package::Pallet::<T>::remove_concluded_packages_and_manifests();
-
-			if let Some(concluded_packages) = concluded_packages {
-				for concluded_package in concluded_packages.iter() {
-					pallet_carrier::Pallet::<T>::remove_concluded_proposals(
-						concluded_package.clone(),
-					);
-				}
+		fn on_idle(n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			// Remove Delivered, Cancelled, Returned, Lost & Expired packages
+			// along with associated manifests and proposals once their retention period has passed
+			let mut consumed = <T as Config>::WeightInfo::on_idle(0);
+
+			if remaining_weight.any_lt(consumed) {
+				return Weight::zero()
+			}
+
+			let max_pruned = <T as pallet_package::Config>::MaxConcludedPackages::get();
+			let mut pruned = 0;
+
+			// The number of proposals is only known once a package has been pruned,
+			// so only continue while there is room for a package with the most proposals
+			while pruned < max_pruned &&
+				remaining_weight.all_gte(consumed.saturating_add(
+					<T as Config>::WeightInfo::prune_concluded(
+						<T as pallet_carrier::Config>::MaxProposalsPerPackage::get(),
+					),
+				)) {
+				let Some(proposals) = Self::prune_next(n) else { break };
+
+				consumed =
+					consumed.saturating_add(<T as Config>::WeightInfo::prune_concluded(proposals));
+				pruned += 1;
+			}
+
+			consumed
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(
+			_n: BlockNumberFor<T>,
+		) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Maximum fee reserved from the client when a carrier's proposal was accepted
+	pub fn reserved_fee(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let max_fee = T::Proposals::proposal_maximum_fee_amount(client, package_id, carrier)
+			.ok_or(pallet_carrier::Error::<T>::ProposalDoesNotExist)?;
+
+		Ok(max_fee)
+	}
+
+	/// Release the maximum fee and ernest deposit reserved against an assigned package
+	pub fn release_reserves(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult {
+		let max_fee = Self::reserved_fee(client, package_id, carrier)?;
+
+		<T as pallet::Config>::Currency::unreserve(&client, max_fee);
+		T::Packages::release_ernest_deposit(client, package_id);
+
+		Ok(())
+	}
+
+	/// Deliver an assigned package and pay its carrier `share` of the final fee,
+	/// returning the rest of the reserved fee to the client
+	pub(crate) fn settle_delivery(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+		share: Perbill,
+	) -> DispatchResult {
+		// Only the assigned carrier can be paid for the delivery
+		T::Packages::ensure_carrier(client, package_id, carrier)?;
+
+		// Mark package as delivered
+		// Only packages which are in transit or disputed can be delivered
+		T::Packages::deliver_package(client, package_id)?;
+
+		let package = T::Packages::package(client, package_id)
+			.ok_or(pallet_package::Error::<T>::PackageDoesNotExist)?;
+
+		// Pay carrier, less the marketplace's commission
+		let final_fee = share *
+			T::Proposals::calculate_final_fee_amount(
+				client,
+				package_id,
+				carrier,
+				package.collected_on.unwrap_or(<frame_system::Pallet<T>>::block_number()),
+				<frame_system::Pallet<T>>::block_number(),
+			)?;
+
+		// Unreserve Maximum fee amount and ernest deposit
+		Self::release_reserves(client, package_id, carrier)?;
+
+		let commission = Parameters::<T>::get().commission_rate * final_fee;
+
+		// Transfer final fee amount to carrier and the commission to the marketplace
+		<T as pallet::Config>::Currency::transfer(
+			client,
+			carrier,
+			final_fee.saturating_sub(commission),
+			ExistenceRequirement::KeepAlive,
+		)?;
+		if !commission.is_zero() {
+			let imbalance = <T as pallet::Config>::Currency::withdraw(
+				client,
+				commission,
+				WithdrawReasons::TRANSFER,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			T::Commission::on_unbalanced(imbalance);
+		}
+		SettledFees::<T>::insert(client, package_id, final_fee);
+
+		// Start the claim period for any cover bought on this package
+		pallet_insurance::Pallet::<T>::conclude_cover(client, package_id);
+
+		Self::deposit_event(Event::PackageDelivered {
+			client: client.clone(),
+			package: *package_id,
+			commission,
+		});
+
+		Ok(())
+	}
+
+	/// Write a package's archive record to offchain storage via offchain indexing
+	pub fn archive_package(client: &T::AccountId, package_id: &PackageId) {
+		let Some(package) = T::Packages::package(client, package_id) else { return };
+
+		let proposal = package
+			.carrier
+			.as_ref()
+			.and_then(|carrier| T::Proposals::proposal(client, package_id, carrier));
+
+		let record = ArchivedPackage::<T> {
+			manifest: T::Packages::manifest(client, package_id),
+			proposal,
+			final_fee: SettledFees::<T>::take(client, package_id),
+			package,
+		};
+
+		sp_io::offchain_index::set(&archive_key(client, package_id), &record.encode());
+	}
+
+	/// Packages a client has created and which have not been pruned yet,
+	/// skipping the first `offset` and returning at most `limit` (capped at `MAX_PAGE_SIZE`)
+	pub fn packages_of(
+		client: &T::AccountId,
+		offset: u32,
+		limit: u32,
+	) -> Vec<(PackageId, Package<T>)> {
+		T::Packages::packages_of(client, offset, limit.min(MAX_PAGE_SIZE))
+	}
+
+	/// Package with the given derived ID along with its client, for when only the ID is known
+	pub fn package_by_id(package_id: &PackageId) -> Option<(T::AccountId, Package<T>)> {
+		T::Packages::package_by_id(package_id)
+	}
+
+	/// Package a client knows by `reference` in their own systems
+	pub fn package_by_reference(client: &T::AccountId, reference: &[u8]) -> Option<PackageId> {
+		T::Packages::package_by_reference(client, reference)
+	}
+
+	/// Metadata the client has attached to a package, as (key, value)
+	pub fn metadata(client: &T::AccountId, package_id: &PackageId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		T::Packages::metadata(client, package_id)
+			.into_iter()
+			.map(|(key, value)| (key.into_inner(), value.into_inner()))
+			.collect()
+	}
+
+	/// Job board of packages waiting for a carrier, paged like `packages_of`
+	pub fn open_packages(offset: u32, limit: u32) -> Vec<Package<T>> {
+		Self::packages_at(T::Packages::packages_with_status(
+			PackageStatus::New,
+			offset,
+			limit.min(MAX_PAGE_SIZE),
+		))
+	}
+
+	/// Packages assigned to a carrier which have not concluded yet, paged like `packages_of`
+	pub fn carrier_workload(carrier: &T::AccountId, offset: u32, limit: u32) -> Vec<Package<T>> {
+		Self::packages_at(
+			T::Packages::carrier_workload(carrier)
+				.into_iter()
+				.skip(offset as usize)
+				.take(limit.min(MAX_PAGE_SIZE) as usize),
+		)
+	}
+
+	fn packages_at(keys: impl IntoIterator<Item = (T::AccountId, PackageId)>) -> Vec<Package<T>> {
+		keys.into_iter()
+			.filter_map(|(client, package_id)| T::Packages::package(&client, &package_id))
+			.collect()
+	}
+
+	/// Proposals made on a package whatever their status, paged like `packages_of`
+	pub fn proposals_for(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		offset: u32,
+		limit: u32,
+	) -> Vec<Proposal<T>> {
+		T::Proposals::proposals_for(client, package_id, offset, limit.min(MAX_PAGE_SIZE))
+	}
+
+	/// Proposals a carrier has made across all clients, paged like `packages_of`.
+	/// Proposals are not indexed by carrier, so this is only suitable for use off-chain.
+	pub fn proposals_by_carrier(
+		carrier: &T::AccountId,
+		offset: u32,
+		limit: u32,
+	) -> Vec<Proposal<T>> {
+		T::Proposals::proposals_by_carrier(carrier, offset, limit.min(MAX_PAGE_SIZE))
+	}
+
+	/// Fee the carrier would be paid if the package were delivered at `delivered_at`
+	pub fn quote_final_fee(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+		delivered_at: BlockNumberFor<T>,
+	) -> Option<BalanceOf<T>> {
+		let package = T::Packages::package(client, package_id)?;
+
+		// Packages which have not been collected yet are quoted as if collected now
+		let collected_on = package.collected_on.unwrap_or(delivered_at);
+		if delivered_at < collected_on {
+			return None
+		}
+
+		T::Proposals::calculate_final_fee_amount(
+			client,
+			package_id,
+			carrier,
+			collected_on,
+			delivered_at,
+		)
+		.ok()
+	}
+
+	/// Archive and remove the oldest concluded package if its retention period has passed,
+	/// returning the number of proposals removed along with it
+	pub fn prune_next(now: BlockNumberFor<T>) -> Option<u32> {
+		let (client, package_id) = T::Packages::next_prunable(now)?;
+
+		// Keep a copy in the node's offchain database before removing anything
+		Self::archive_package(&client, &package_id);
+
+		T::Packages::prune_next_concluded(now);
+		pallet_insurance::Pallet::<T>::remove_cover(&client, &package_id);
+		Some(T::Proposals::remove_concluded_proposals((client, package_id)))
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Names of the marketplace calls which can be paused.
+	/// Packages and proposals can only be changed through this pallet.
+	fn pausable_calls(pallet_name: &[u8]) -> Option<&'static [&'static str]> {
+		if pallet_name == <Self as PalletInfoAccess>::name().as_bytes() {
+			Some(Call::<T>::get_call_names())
+		} else {
+			None
+		}
+	}
+
+	/// Check the pallet exists and, if a call is named, that the pallet has it.
+	/// Pausing and unpausing themselves can never be paused.
+	pub(crate) fn ensure_pausable(pallet_name: &Name, call_name: &Option<Name>) -> DispatchResult {
+		let calls = Self::pausable_calls(pallet_name).ok_or(Error::<T>::NotPausable)?;
+
+		if let Some(call_name) = call_name {
+			ensure!(
+				calls.iter().any(|call| call.as_bytes() == &call_name[..]),
+				Error::<T>::NotPausable
+			);
+			ensure!(!Self::is_pause_call(pallet_name, call_name), Error::<T>::NotPausable);
+		}
+
+		Ok(())
+	}
+
+	fn is_pause_call(pallet_name: &[u8], call_name: &[u8]) -> bool {
+		pallet_name == <Self as PalletInfoAccess>::name().as_bytes() &&
+			matches!(call_name, b"pause" | b"unpause")
+	}
+
+	/// Whether the call has been paused, either on its own or along with its pallet
+	pub fn is_paused(pallet_name: &str, call_name: &str) -> bool {
+		// Only this pallet's calls can be paused, so other calls never touch storage
+		if pallet_name != <Self as PalletInfoAccess>::name() ||
+			Self::is_pause_call(pallet_name.as_bytes(), call_name.as_bytes())
+		{
+			return false
+		}
+
+		let Ok(pallet_name) = Name::try_from(pallet_name.as_bytes().to_vec()) else { return false };
+
+		PausedCalls::<T>::contains_key(&pallet_name, None::<Name>) ||
+			Name::try_from(call_name.as_bytes().to_vec()).map_or(false, |call_name| {
+				PausedCalls::<T>::contains_key(&pallet_name, Some(call_name))
+			})
+	}
+}
+
+/// Runtime call filter which blocks paused calls, unless `SafeCalls` contains them.
+/// Use it as `frame_system::Config::BaseCallFilter`.
+pub struct PauseFilter<T, SafeCalls>(PhantomData<(T, SafeCalls)>);
+
+impl<T, SafeCalls> Contains<<T as frame_system::Config>::RuntimeCall> for PauseFilter<T, SafeCalls>
+where
+	T: Config,
+	<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
+	SafeCalls: Contains<<T as frame_system::Config>::RuntimeCall>,
+{
+	fn contains(call: &<T as frame_system::Config>::RuntimeCall) -> bool {
+		if SafeCalls::contains(call) {
+			return true
+		}
+
+		let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+		!Pallet::<T>::is_paused(pallet_name, function_name)
+	}
+}
+
+/// Implements `Get` for a single marketplace parameter,
+/// so that other pallets can be configured with its current value
+macro_rules! parameter_getter {
+	($(#[$doc:meta])* $name:ident, $field:ident, $value:ty) => {
+		$(#[$doc])*
+		pub struct $name<T>(PhantomData<T>);
+
+		impl<T: Config> Get<$value> for $name<T> {
+			fn get() -> $value {
+				Parameters::<T>::get().$field
 			}
+		}
+	};
+}
+
+parameter_getter!(
+	/// Current ernest deposit, for `pallet_package::Config::ErnestDeposit`
+	ErnestDepositParameter,
+	ernest_deposit,
+	BalanceOf<T>
+);
+parameter_getter!(
+	/// Current proposal deposit, for `pallet_carrier::Config::ProposalDeposit`
+	ProposalDepositParameter,
+	proposal_deposit,
+	BalanceOf<T>
+);
+parameter_getter!(
+	/// Current retention period, for `pallet_package::Config::ConcludedRetentionPeriod`
+	ConcludedRetentionPeriodParameter,
+	concluded_retention_period,
+	BlockNumberFor<T>
+);
+parameter_getter!(
+	/// Current package expiry, for `pallet_package::Config::PackageExpiry`
+	PackageExpiryParameter,
+	package_expiry,
+	BlockNumberFor<T>
+);
+parameter_getter!(
+	/// Current base rate, for `pallet_insurance::Config::BaseRate`
+	InsuranceBaseRateParameter,
+	insurance_base_rate,
+	Permill
+);
+parameter_getter!(
+	/// Current surcharge, for `pallet_insurance::Config::HazardousMaterialsSurcharge`
+	HazardousMaterialsSurchargeParameter,
+	hazardous_materials_surcharge,
+	Permill
+);
+parameter_getter!(
+	/// Current surcharge, for `pallet_insurance::Config::ClimateControlledSurcharge`
+	ClimateControlledSurchargeParameter,
+	climate_controlled_surcharge,
+	Permill
+);
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config> Pallet<T> {
+	/// Check that packages, proposals and reserved funds agree with each other
+	pub fn do_try_state() -> Result<(), DispatchError> {
+		use frame_support::sp_runtime::traits::CheckedAdd;
+		use pallet_carrier::types::ProposalStatus;
+		use sp_std::collections::btree_map::BTreeMap;
+
+		const OVERFLOW: &str = "Funds required to be reserved overflow the balance type";
+
+		// Funds each client must have reserved for their packages
+		let mut reserved = BTreeMap::<T::AccountId, BalanceOf<T>>::new();
 
-			Weight::zero()
+		for (client, package_id, package) in pallet_package::Packages::<T>::iter() {
+			// Metadata deposits are held until the package is pruned, whatever its status
+			let total = reserved.entry(client.clone()).or_default();
+			*total = total
+				.checked_add(&pallet_package::MetadataDeposits::<T>::get(&client, &package_id))
+				.ok_or(OVERFLOW)?;
+
+			if package.status.is_concluded() {
+				ensure!(
+					!pallet_package::ErnestDeposits::<T>::contains_key(&client, &package_id),
+					"Ernest deposit is still held for a concluded package"
+				);
+				continue
+			}
+
+			let mut required = pallet_package::ErnestDeposits::<T>::get(&client, &package_id);
+
+			if let Some(carrier) = package.carrier {
+				let accepted: Vec<_> =
+					pallet_carrier::Proposals::<T>::iter_prefix_values((&client, &package_id))
+						.filter(|proposal| proposal.status == ProposalStatus::Accepted)
+						.collect();
+
+				ensure!(
+					accepted.len() == 1 && accepted[0].carrier == carrier,
+					"Assigned package does not have exactly one accepted proposal from its carrier"
+				);
+
+				required = required
+					.checked_add(&Self::reserved_fee(&client, &package_id, &carrier)?)
+					.ok_or(OVERFLOW)?;
+			}
+
+			let total = reserved.entry(client).or_default();
+			*total = total.checked_add(&required).ok_or(OVERFLOW)?;
+		}
+
+		for (client, required) in reserved {
+			ensure!(
+				<T as pallet::Config>::Currency::reserved_balance(&client) >= required,
+				"Client has reserved less than the deposits and fees of their packages"
+			);
 		}
+
+		let parameters = Parameters::<T>::get();
+		let (minimum, maximum) =
+			(<T as Config>::MinimumParameters::get(), <T as Config>::MaximumParameters::get());
+		ensure!(
+			[
+				MarketplaceParameter::ErnestDeposit(parameters.ernest_deposit),
+				MarketplaceParameter::ProposalDeposit(parameters.proposal_deposit),
+				MarketplaceParameter::ConcludedRetentionPeriod(
+					parameters.concluded_retention_period
+				),
+				MarketplaceParameter::PackageExpiry(parameters.package_expiry),
+				MarketplaceParameter::InsuranceBaseRate(parameters.insurance_base_rate),
+				MarketplaceParameter::HazardousMaterialsSurcharge(
+					parameters.hazardous_materials_surcharge
+				),
+				MarketplaceParameter::ClimateControlledSurcharge(
+					parameters.climate_controlled_surcharge
+				),
+				MarketplaceParameter::CommissionRate(parameters.commission_rate),
+			]
+			.iter()
+			.all(|parameter| parameter.is_within(&minimum, &maximum)),
+			"Marketplace parameter lies outside of its bounds"
+		);
+
+		for (client, package_id) in SettledFees::<T>::iter_keys() {
+			ensure!(
+				T::Packages::package(&client, &package_id)
+					.map_or(false, |package| package.status.is_concluded()),
+				"Settled fee is kept for a package which has not concluded"
+			);
+		}
+
+		Ok(())
 	}
 }
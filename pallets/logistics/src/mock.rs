@@ -0,0 +1,223 @@
+use crate::{
+	self as pallet_logistics, types::MarketplaceParameters, ClimateControlledSurchargeParameter,
+	ConcludedRetentionPeriodParameter, ErnestDepositParameter,
+	HazardousMaterialsSurchargeParameter, InsuranceBaseRateParameter, PackageExpiryParameter,
+	ProposalDepositParameter,
+};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, Contains},
+	PalletId,
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage, Permill,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Timestamp: pallet_timestamp,
+		Balances: pallet_balances,
+		PackageModule: pallet_package,
+		CarrierModule: pallet_carrier,
+		InsuranceModule: pallet_insurance,
+		LogisticsModule: pallet_logistics,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = pallet_logistics::PauseFilter<Test, SafeExits>;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+/// Calls which stay available while the marketplace is paused
+pub struct SafeExits;
+
+impl Contains<RuntimeCall> for SafeExits {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::LogisticsModule(pallet_logistics::Call::package_cancel { .. }) |
+				RuntimeCall::LogisticsModule(pallet_logistics::Call::proposal_withdraw { .. })
+		)
+	}
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<5>;
+	type WeightInfo = ();
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = ();
+	type MaxHolds = ();
+}
+
+impl pallet_package::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ErnestDeposit = ErnestDepositParameter<Test>;
+	type Slashed = ();
+	type DescriptionMaxLength = ConstU32<64>;
+	type MaxConcludedPackages = ConstU32<16>;
+	type ConcludedRetentionPeriod = ConcludedRetentionPeriodParameter<Test>;
+	type PackageExpiry = PackageExpiryParameter<Test>;
+	type UnixTime = Timestamp;
+	type MaxTimelineEntries = ConstU32<16>;
+	type MaxCarrierPackages = ConstU32<16>;
+	type MaxMetadataEntries = ConstU32<4>;
+	type MaxMetadataKeyLength = ConstU32<16>;
+	type MaxMetadataValueLength = ConstU32<32>;
+	type MaxReferenceLength = ConstU32<32>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type MaxLineItems = ConstU32<4>;
+	type MaxSkuLength = ConstU32<16>;
+}
+
+impl pallet_carrier::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ProposalDeposit = ProposalDepositParameter<Test>;
+	type MaxProposalsPerPackage = ConstU32<3>;
+}
+
+parameter_types! {
+	pub const InsurancePalletId: PalletId = PalletId(*b"lgx/insr");
+}
+
+impl pallet_insurance::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type PalletId = InsurancePalletId;
+	type BaseRate = InsuranceBaseRateParameter<Test>;
+	type HazardousMaterialsSurcharge = HazardousMaterialsSurchargeParameter<Test>;
+	type ClimateControlledSurcharge = ClimateControlledSurchargeParameter<Test>;
+	type ClaimPeriod = ConstU64<5>;
+	type ClaimOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const DefaultParameters: MarketplaceParameters<u64, u64> = MarketplaceParameters {
+		ernest_deposit: 10,
+		proposal_deposit: 5,
+		concluded_retention_period: 10,
+		package_expiry: 100,
+		insurance_base_rate: Permill::from_percent(1),
+		hazardous_materials_surcharge: Permill::from_percent(2),
+		climate_controlled_surcharge: Permill::from_percent(1),
+		commission_rate: Permill::zero(),
+	};
+	pub const MinimumParameters: MarketplaceParameters<u64, u64> = MarketplaceParameters {
+		ernest_deposit: 1,
+		proposal_deposit: 1,
+		concluded_retention_period: 5,
+		package_expiry: 50,
+		insurance_base_rate: Permill::zero(),
+		hazardous_materials_surcharge: Permill::zero(),
+		climate_controlled_surcharge: Permill::zero(),
+		commission_rate: Permill::zero(),
+	};
+	pub const MaximumParameters: MarketplaceParameters<u64, u64> = MarketplaceParameters {
+		ernest_deposit: 100,
+		proposal_deposit: 50,
+		concluded_retention_period: 100,
+		package_expiry: 1_000,
+		insurance_base_rate: Permill::from_percent(10),
+		hazardous_materials_surcharge: Permill::from_percent(10),
+		climate_controlled_surcharge: Permill::from_percent(10),
+		commission_rate: Permill::from_percent(10),
+	};
+}
+
+impl pallet_logistics::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type Slashed = ();
+	type Commission = ();
+	type Packages = PackageModule;
+	type Proposals = CarrierModule;
+	type ParametersOrigin = EnsureRoot<u64>;
+	type DefaultParameters = DefaultParameters;
+	type MinimumParameters = MinimumParameters;
+	type MaximumParameters = MaximumParameters;
+	type PauseOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+pub const CLIENT: u64 = 1;
+pub const CARRIER: u64 = 2;
+pub const OTHER_CARRIER: u64 = 3;
+pub const THIRD_CARRIER: u64 = 4;
+pub const UNDERWRITER: u64 = 5;
+/// Account with no funds
+pub const NOBODY: u64 = 6;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(CLIENT, 1_000),
+			(CARRIER, 1_000),
+			(OTHER_CARRIER, 1_000),
+			(THIRD_CARRIER, 1_000),
+			(UNDERWRITER, 10_000),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Run a test and check the pallet's invariants once it has finished
+pub fn build_and_execute(test: impl FnOnce()) {
+	new_test_ext().execute_with(|| {
+		test();
+		LogisticsModule::do_try_state().expect("state is consistent");
+	});
+}
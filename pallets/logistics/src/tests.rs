@@ -0,0 +1,1345 @@
+use crate::{
+	mock::*,
+	types::{MarketplaceParameter, Name},
+	Error, Event, Parameters, PausedCalls, SettledFees,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::DispatchResult,
+	traits::{Get, Hooks},
+	weights::Weight,
+};
+use pallet_carrier::types::ProposalStatus;
+use pallet_package::types::{PackageId, PackageStatus};
+use sp_runtime::{traits::Dispatchable, DispatchError::BadOrigin, Permill};
+
+const PACKAGE_ID: PackageId = [1u8; 32];
+
+fn create_package() {
+	assert_ok!(LogisticsModule::package_create(
+		RuntimeOrigin::signed(CLIENT),
+		Some(PACKAGE_ID),
+		b"filled.count.soap".to_vec(),
+		b"index.home.raft".to_vec(),
+		b"Books".to_vec(),
+		10,
+		20,
+		30,
+		5,
+		false,
+		false,
+		500,
+	));
+}
+
+/// Proposal with a maximum fee of 100, losing 15 every 10 blocks down to a minimum of 40
+fn propose(carrier: u64) {
+	propose_fees(carrier, 100, 40);
+}
+
+fn propose_fees(carrier: u64, maximum_fee: u64, minimum_fee: u64) {
+	assert_ok!(LogisticsModule::proposal_create(
+		RuntimeOrigin::signed(carrier),
+		CLIENT,
+		PACKAGE_ID,
+		maximum_fee,
+		minimum_fee,
+		10,
+		15,
+	));
+}
+
+fn assign(carrier: u64) {
+	assert_ok!(LogisticsModule::package_assign(
+		RuntimeOrigin::signed(CLIENT),
+		PACKAGE_ID,
+		carrier,
+		None
+	));
+}
+
+/// Create a package, accept `CARRIER`'s proposal and have it collected at block 1
+fn collected_package() {
+	create_package();
+	propose(CARRIER);
+	assign(CARRIER);
+	assert_ok!(LogisticsModule::package_collect(
+		RuntimeOrigin::signed(CARRIER),
+		PACKAGE_ID,
+		CLIENT
+	));
+}
+
+fn status() -> Option<PackageStatus> {
+	PackageModule::get_package(CLIENT, PACKAGE_ID).map(|package| package.status)
+}
+
+fn proposal_status(carrier: u64) -> Option<ProposalStatus> {
+	CarrierModule::get_proposal((CLIENT, PACKAGE_ID, carrier)).map(|proposal| proposal.status)
+}
+
+#[test]
+fn package_create_reserves_ernest_deposit() {
+	build_and_execute(|| {
+		create_package();
+
+		assert_eq!(status(), Some(PackageStatus::New));
+		assert_eq!(Balances::reserved_balance(CLIENT), 10);
+		assert_noop!(
+			LogisticsModule::package_create(
+				RuntimeOrigin::signed(NOBODY),
+				Some(PACKAGE_ID),
+				b"filled.count.soap".to_vec(),
+				b"index.home.raft".to_vec(),
+				b"Books".to_vec(),
+				10,
+				20,
+				30,
+				5,
+				false,
+				false,
+				500,
+			),
+			pallet_package::Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+fn create_package_without_id(client: u64) -> PackageId {
+	assert_ok!(LogisticsModule::package_create(
+		RuntimeOrigin::signed(client),
+		None,
+		b"filled.count.soap".to_vec(),
+		b"index.home.raft".to_vec(),
+		b"Books".to_vec(),
+		10,
+		20,
+		30,
+		5,
+		false,
+		false,
+		500,
+	));
+
+	match System::events().pop().map(|record| record.event) {
+		Some(RuntimeEvent::PackageModule(pallet_package::Event::PackageCreated {
+			client: created_by,
+			package,
+		})) if created_by == client => package,
+		event => panic!("expected a PackageCreated event, found {:?}", event),
+	}
+}
+
+#[test]
+fn package_create_derives_an_id_when_none_is_given() {
+	build_and_execute(|| {
+		let first = create_package_without_id(CLIENT);
+		let second = create_package_without_id(CLIENT);
+		let other = create_package_without_id(CARRIER);
+
+		// Derived IDs differ even for the same client in the same block
+		assert_ne!(first, second);
+		assert_ne!(first, other);
+		assert_eq!(LogisticsModule::package_by_id(&first).map(|(client, _)| client), Some(CLIENT));
+		assert_eq!(LogisticsModule::package_by_id(&other).map(|(client, _)| client), Some(CARRIER));
+	});
+}
+
+#[test]
+fn chosen_package_ids_are_scoped_to_their_client() {
+	build_and_execute(|| {
+		create_package();
+
+		let create_as = |client| {
+			LogisticsModule::package_create(
+				RuntimeOrigin::signed(client),
+				Some(PACKAGE_ID),
+				b"filled.count.soap".to_vec(),
+				b"index.home.raft".to_vec(),
+				b"Books".to_vec(),
+				10,
+				20,
+				30,
+				5,
+				false,
+				false,
+				500,
+			)
+		};
+		assert_noop!(create_as(CLIENT), pallet_package::Error::<Test>::PackageExists);
+
+		// Another client can use the same ID without taking it from the first
+		assert_ok!(create_as(CARRIER));
+		assert_eq!(
+			PackageModule::get_package(CLIENT, PACKAGE_ID).map(|package| package.status),
+			Some(PackageStatus::New)
+		);
+		assert_eq!(
+			PackageModule::get_package(CARRIER, PACKAGE_ID).map(|package| package.status),
+			Some(PackageStatus::New)
+		);
+
+		// Only derived IDs can be found without their client
+		assert_eq!(LogisticsModule::package_by_id(&PACKAGE_ID), None);
+	});
+}
+
+#[test]
+fn package_cancel_releases_ernest_deposit() {
+	build_and_execute(|| {
+		create_package();
+
+		assert_ok!(LogisticsModule::package_cancel(RuntimeOrigin::signed(CLIENT), PACKAGE_ID));
+
+		assert_eq!(status(), Some(PackageStatus::Cancelled));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert_eq!(Balances::free_balance(CLIENT), 1_000);
+	});
+}
+
+#[test]
+fn package_cancel_requires_new_package() {
+	build_and_execute(|| {
+		assert_noop!(
+			LogisticsModule::package_cancel(RuntimeOrigin::signed(CLIENT), PACKAGE_ID),
+			pallet_package::Error::<Test>::PackageDoesNotExist
+		);
+
+		create_package();
+		propose(CARRIER);
+		assign(CARRIER);
+
+		assert_noop!(
+			LogisticsModule::package_cancel(RuntimeOrigin::signed(CLIENT), PACKAGE_ID),
+			pallet_package::Error::<Test>::PackageNotNew
+		);
+	});
+}
+
+#[test]
+fn package_assign_reserves_maximum_fee() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		assign(CARRIER);
+
+		let package = PackageModule::get_package(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(package.status, PackageStatus::Assigned);
+		assert_eq!(package.carrier, Some(CARRIER));
+		assert_eq!(proposal_status(CARRIER), Some(ProposalStatus::Accepted));
+
+		// Ernest deposit plus maximum fee
+		assert_eq!(Balances::reserved_balance(CLIENT), 110);
+		assert_eq!(Balances::free_balance(CLIENT), 890);
+		// Carrier's proposal deposit is returned on acceptance
+		assert_eq!(Balances::reserved_balance(CARRIER), 0);
+	});
+}
+
+#[test]
+fn package_assign_rejects_every_other_proposal() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		propose(THIRD_CARRIER);
+
+		assign(OTHER_CARRIER);
+
+		assert_eq!(proposal_status(OTHER_CARRIER), Some(ProposalStatus::Accepted));
+		for carrier in [CARRIER, THIRD_CARRIER] {
+			assert_eq!(proposal_status(carrier), Some(ProposalStatus::Rejected));
+			assert_eq!(Balances::reserved_balance(carrier), 0);
+			System::assert_has_event(
+				pallet_carrier::Event::ProposalRejected {
+					client: CLIENT,
+					package: PACKAGE_ID,
+					carrier,
+				}
+				.into(),
+			);
+		}
+
+		// Rejected carriers can't be assigned instead
+		assert_noop!(
+			LogisticsModule::package_assign(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				None
+			),
+			pallet_package::Error::<Test>::PackageNotNew
+		);
+	});
+}
+
+#[test]
+fn package_assign_requires_valid_package_and_proposal() {
+	build_and_execute(|| {
+		assert_noop!(
+			LogisticsModule::package_assign(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				None
+			),
+			pallet_package::Error::<Test>::PackageDoesNotExist
+		);
+
+		create_package();
+		assert_noop!(
+			LogisticsModule::package_assign(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				None
+			),
+			pallet_carrier::Error::<Test>::InvalidProposal
+		);
+	});
+}
+
+#[test]
+fn package_assign_requires_funds_for_maximum_fee() {
+	build_and_execute(|| {
+		create_package();
+		propose_fees(CARRIER, 991, 40);
+
+		assert_noop!(
+			LogisticsModule::package_assign(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				None
+			),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn reserved_fee_requires_a_proposal() {
+	build_and_execute(|| {
+		create_package();
+
+		assert_eq!(
+			LogisticsModule::reserved_fee(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Err(pallet_carrier::Error::<Test>::ProposalDoesNotExist.into())
+		);
+
+		propose(CARRIER);
+		assert_eq!(LogisticsModule::reserved_fee(&CLIENT, &PACKAGE_ID, &CARRIER), Ok(100));
+	});
+}
+
+#[test]
+fn package_assign_buys_cover() {
+	build_and_execute(|| {
+		assert_ok!(InsuranceModule::pool_deposit(RuntimeOrigin::signed(UNDERWRITER), 5_000));
+		create_package();
+		propose(CARRIER);
+
+		assert_ok!(LogisticsModule::package_assign(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER,
+			Some(500)
+		));
+
+		// 1% premium on the insured value
+		assert_eq!(InsuranceModule::active_cover(), 500);
+		assert_eq!(Balances::free_balance(CLIENT), 885);
+		assert_eq!(InsuranceModule::pool_value(), 5_005);
+	});
+}
+
+#[test]
+fn package_assign_fails_without_pool_capacity() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+
+		assert_noop!(
+			LogisticsModule::package_assign(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				Some(500)
+			),
+			pallet_insurance::Error::<Test>::InsufficientPoolCapacity
+		);
+	});
+}
+
+#[test]
+fn package_assign_rejects_insured_value_above_declared_value() {
+	build_and_execute(|| {
+		assert_ok!(InsuranceModule::pool_deposit(RuntimeOrigin::signed(UNDERWRITER), 5_000));
+		create_package();
+		propose(CARRIER);
+
+		assert_noop!(
+			LogisticsModule::package_assign(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				Some(501)
+			),
+			Error::<Test>::InsuredValueExceedsDeclaredValue
+		);
+	});
+}
+
+fn update_package(declared_value: u64) -> DispatchResult {
+	LogisticsModule::package_update(
+		RuntimeOrigin::signed(CLIENT),
+		PACKAGE_ID,
+		b"filled.count.soap".to_vec(),
+		b"index.home.raft".to_vec(),
+		b"Books".to_vec(),
+		10,
+		20,
+		30,
+		5,
+		false,
+		false,
+		declared_value,
+	)
+}
+
+#[test]
+fn package_update_marks_proposals_stale() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		propose_fees(OTHER_CARRIER, 50, 10);
+
+		assert_ok!(update_package(800));
+
+		let manifest = pallet_package::Manifests::<Test>::get(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(manifest.declared_value, 800);
+		assert_eq!(status(), Some(PackageStatus::New));
+		assert_eq!(proposal_status(CARRIER), Some(ProposalStatus::Stale));
+		assert_eq!(proposal_status(OTHER_CARRIER), Some(ProposalStatus::Stale));
+
+		// Stale proposals keep their deposits but can't be accepted until confirmed
+		assert_eq!(Balances::reserved_balance(CARRIER), 5);
+		assert_noop!(
+			LogisticsModule::package_assign(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				None
+			),
+			pallet_carrier::Error::<Test>::InvalidProposal
+		);
+
+		assert_ok!(LogisticsModule::proposal_confirm(
+			RuntimeOrigin::signed(CARRIER),
+			CLIENT,
+			PACKAGE_ID
+		));
+		assert_eq!(proposal_status(CARRIER), Some(ProposalStatus::Proposed));
+		assign(CARRIER);
+
+		assert_eq!(proposal_status(OTHER_CARRIER), Some(ProposalStatus::Rejected));
+		assert_eq!(Balances::reserved_balance(OTHER_CARRIER), 0);
+	});
+}
+
+#[test]
+fn package_update_requires_new_package() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		assign(CARRIER);
+
+		assert_noop!(update_package(800), pallet_package::Error::<Test>::PackageNotNew);
+		assert_noop!(
+			LogisticsModule::proposal_confirm(RuntimeOrigin::signed(CARRIER), CLIENT, PACKAGE_ID),
+			pallet_carrier::Error::<Test>::InvalidProposal
+		);
+	});
+}
+
+#[test]
+fn proposal_create_requires_new_package() {
+	build_and_execute(|| {
+		assert_noop!(
+			LogisticsModule::proposal_create(
+				RuntimeOrigin::signed(CARRIER),
+				CLIENT,
+				PACKAGE_ID,
+				100,
+				40,
+				10,
+				15
+			),
+			pallet_package::Error::<Test>::PackageDoesNotExist
+		);
+
+		create_package();
+		propose(CARRIER);
+		assign(CARRIER);
+
+		assert_noop!(
+			LogisticsModule::proposal_create(
+				RuntimeOrigin::signed(OTHER_CARRIER),
+				CLIENT,
+				PACKAGE_ID,
+				100,
+				40,
+				10,
+				15
+			),
+			pallet_package::Error::<Test>::PackageNotNew
+		);
+	});
+}
+
+#[test]
+fn proposal_withdraw_returns_deposit() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+
+		assert_ok!(LogisticsModule::proposal_withdraw(
+			RuntimeOrigin::signed(CARRIER),
+			CLIENT,
+			PACKAGE_ID
+		));
+
+		assert_eq!(proposal_status(CARRIER), None);
+		assert_eq!(Balances::reserved_balance(CARRIER), 0);
+
+		// Accepted proposals can no longer be withdrawn
+		propose(CARRIER);
+		assign(CARRIER);
+		assert_noop!(
+			LogisticsModule::proposal_withdraw(RuntimeOrigin::signed(CARRIER), CLIENT, PACKAGE_ID),
+			pallet_carrier::Error::<Test>::InvalidProposal
+		);
+	});
+}
+
+#[test]
+fn package_deliver_pays_final_fee() {
+	build_and_execute(|| {
+		collected_package();
+
+		// Two penalty periods have passed
+		System::set_block_number(25);
+		assert_ok!(LogisticsModule::package_deliver(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER
+		));
+
+		assert_eq!(status(), Some(PackageStatus::Delivered));
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), Some(70));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert_eq!(Balances::free_balance(CLIENT), 930);
+		assert_eq!(Balances::free_balance(CARRIER), 1_070);
+		System::assert_last_event(
+			Event::PackageDelivered { client: CLIENT, package: PACKAGE_ID, commission: 0 }.into(),
+		);
+	});
+}
+
+#[test]
+fn package_deliver_keeps_the_marketplace_commission() {
+	build_and_execute(|| {
+		collected_package();
+		assert_ok!(LogisticsModule::set_parameter(
+			RuntimeOrigin::root(),
+			MarketplaceParameter::CommissionRate(Permill::from_percent(10))
+		));
+		let issuance = Balances::total_issuance();
+
+		System::set_block_number(25);
+		assert_ok!(LogisticsModule::package_deliver(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER
+		));
+
+		// The client pays the whole fee, of which the carrier receives all but the commission
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), Some(70));
+		assert_eq!(Balances::free_balance(CLIENT), 930);
+		assert_eq!(Balances::free_balance(CARRIER), 1_063);
+		assert_eq!(Balances::total_issuance(), issuance - 7);
+		System::assert_last_event(
+			Event::PackageDelivered { client: CLIENT, package: PACKAGE_ID, commission: 7 }.into(),
+		);
+	});
+}
+
+#[test]
+fn package_deliver_pays_at_least_minimum_fee() {
+	build_and_execute(|| {
+		collected_package();
+
+		System::set_block_number(1_000);
+		assert_ok!(LogisticsModule::package_deliver(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER
+		));
+
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), Some(40));
+		assert_eq!(Balances::free_balance(CLIENT), 960);
+		assert_eq!(Balances::free_balance(CARRIER), 1_040);
+	});
+}
+
+#[test]
+fn package_deliver_requires_assigned_carrier() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		propose_fees(OTHER_CARRIER, 50, 10);
+		assign(CARRIER);
+		assert_ok!(LogisticsModule::package_collect(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+
+		// A rejected carrier can't be paid in place of the assigned one
+		assert_noop!(
+			LogisticsModule::package_deliver(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				OTHER_CARRIER
+			),
+			pallet_package::Error::<Test>::InvalidCarrier
+		);
+	});
+}
+
+/// Two mugs worth 50 each and two plates worth 75 each
+fn set_line_items() {
+	assert_ok!(LogisticsModule::package_set_line_items(
+		RuntimeOrigin::signed(CLIENT),
+		PACKAGE_ID,
+		vec![
+			(b"MUG".to_vec(), b"Mug".to_vec(), 2, 1, 50),
+			(b"PLATE".to_vec(), b"Plate".to_vec(), 2, 2, 75),
+		],
+	));
+}
+
+fn deliver_partial(received: Vec<u32>) {
+	assert_ok!(LogisticsModule::package_deliver_partial(
+		RuntimeOrigin::signed(CLIENT),
+		PACKAGE_ID,
+		CARRIER,
+		received
+	));
+}
+
+#[test]
+fn package_deliver_partial_pays_share_of_final_fee() {
+	build_and_execute(|| {
+		create_package();
+		set_line_items();
+		propose(CARRIER);
+		assign(CARRIER);
+		assert_ok!(LogisticsModule::package_collect(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+
+		// Only the mugs arrived, 40% of the declared value
+		System::set_block_number(25);
+		deliver_partial(vec![2, 0]);
+
+		assert_eq!(status(), Some(PackageStatus::Delivered));
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), Some(28));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert_eq!(Balances::free_balance(CLIENT), 972);
+		assert_eq!(Balances::free_balance(CARRIER), 1_028);
+	});
+}
+
+#[test]
+fn package_deliver_partial_requires_line_items() {
+	build_and_execute(|| {
+		collected_package();
+
+		assert_noop!(
+			LogisticsModule::package_deliver_partial(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				CARRIER,
+				vec![]
+			),
+			pallet_package::Error::<Test>::NoLineItems
+		);
+	});
+}
+
+#[test]
+fn package_set_line_items_requires_new_package() {
+	build_and_execute(|| {
+		collected_package();
+
+		assert_noop!(
+			LogisticsModule::package_set_line_items(
+				RuntimeOrigin::signed(CLIENT),
+				PACKAGE_ID,
+				vec![(b"MUG".to_vec(), b"Mug".to_vec(), 2, 1, 50)],
+			),
+			pallet_package::Error::<Test>::PackageNotNew
+		);
+	});
+}
+
+#[test]
+fn claim_missing_items_claims_missing_value_up_to_insured_value() {
+	build_and_execute(|| {
+		assert_ok!(InsuranceModule::pool_deposit(RuntimeOrigin::signed(UNDERWRITER), 5_000));
+		create_package();
+		set_line_items();
+		propose(CARRIER);
+		assert_ok!(LogisticsModule::package_assign(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER,
+			Some(100)
+		));
+		assert_ok!(LogisticsModule::package_collect(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+		deliver_partial(vec![2, 0]);
+
+		// The missing plates are worth 150 but only 100 was insured
+		assert_ok!(LogisticsModule::claim_missing_items(RuntimeOrigin::signed(CLIENT), PACKAGE_ID));
+		assert_eq!(
+			InsuranceModule::get_claim(CLIENT, PACKAGE_ID).map(|claim| claim.amount),
+			Some(100)
+		);
+	});
+}
+
+#[test]
+fn claim_missing_items_requires_missing_items() {
+	build_and_execute(|| {
+		assert_ok!(InsuranceModule::pool_deposit(RuntimeOrigin::signed(UNDERWRITER), 5_000));
+		create_package();
+		set_line_items();
+		propose(CARRIER);
+		assert_ok!(LogisticsModule::package_assign(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER,
+			Some(100)
+		));
+
+		// Nothing can be missing before delivery has been confirmed
+		assert_noop!(
+			LogisticsModule::claim_missing_items(RuntimeOrigin::signed(CLIENT), PACKAGE_ID),
+			pallet_package::Error::<Test>::NoLineItems
+		);
+
+		assert_ok!(LogisticsModule::package_collect(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+		deliver_partial(vec![2, 2]);
+
+		assert_noop!(
+			LogisticsModule::claim_missing_items(RuntimeOrigin::signed(CLIENT), PACKAGE_ID),
+			Error::<Test>::NothingMissing
+		);
+	});
+}
+
+#[test]
+fn package_deliver_requires_package_in_transit() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		assign(CARRIER);
+
+		assert_noop!(
+			LogisticsModule::package_deliver(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, CARRIER),
+			pallet_package::Error::<Test>::PackageCannotBeDelivered
+		);
+	});
+}
+
+#[test]
+fn package_collect_requires_assigned_carrier() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		assign(CARRIER);
+
+		assert_noop!(
+			LogisticsModule::package_collect(
+				RuntimeOrigin::signed(OTHER_CARRIER),
+				PACKAGE_ID,
+				CLIENT
+			),
+			pallet_package::Error::<Test>::InvalidCarrier
+		);
+		assert_ok!(LogisticsModule::package_collect(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+		assert_eq!(status(), Some(PackageStatus::InTransit));
+	});
+}
+
+#[test]
+fn holds_are_released_by_the_carrier() {
+	build_and_execute(|| {
+		collected_package();
+
+		assert_noop!(
+			LogisticsModule::package_hold(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, CLIENT),
+			pallet_package::Error::<Test>::InvalidCarrier
+		);
+		assert_ok!(LogisticsModule::package_hold(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+		assert_eq!(status(), Some(PackageStatus::OnHold));
+		assert_noop!(
+			LogisticsModule::package_hold(RuntimeOrigin::signed(CARRIER), PACKAGE_ID, CLIENT),
+			pallet_package::Error::<Test>::PackageNotInTransit
+		);
+
+		assert_noop!(
+			LogisticsModule::package_release(RuntimeOrigin::signed(CLIENT), PACKAGE_ID, CLIENT),
+			pallet_package::Error::<Test>::InvalidCarrier
+		);
+		assert_ok!(LogisticsModule::package_release(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+		assert_eq!(status(), Some(PackageStatus::InTransit));
+		assert_noop!(
+			LogisticsModule::package_release(RuntimeOrigin::signed(CARRIER), PACKAGE_ID, CLIENT),
+			pallet_package::Error::<Test>::PackageCannotBeReleased
+		);
+	});
+}
+
+#[test]
+fn disputes_are_withdrawn_by_the_client() {
+	build_and_execute(|| {
+		collected_package();
+
+		assert_ok!(LogisticsModule::package_dispute(RuntimeOrigin::signed(CLIENT), PACKAGE_ID));
+		assert_eq!(status(), Some(PackageStatus::Disputed));
+		assert_noop!(
+			LogisticsModule::package_dispute(RuntimeOrigin::signed(CLIENT), PACKAGE_ID),
+			pallet_package::Error::<Test>::PackageCannotBeDisputed
+		);
+
+		assert_noop!(
+			LogisticsModule::package_release(RuntimeOrigin::signed(CARRIER), PACKAGE_ID, CLIENT),
+			pallet_package::Error::<Test>::InvalidPackage
+		);
+		assert_ok!(LogisticsModule::package_release(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CLIENT
+		));
+		assert_eq!(status(), Some(PackageStatus::InTransit));
+	});
+}
+
+#[test]
+fn package_return_pays_minimum_fee() {
+	build_and_execute(|| {
+		collected_package();
+
+		assert_noop!(
+			LogisticsModule::package_return(
+				RuntimeOrigin::signed(OTHER_CARRIER),
+				PACKAGE_ID,
+				CLIENT
+			),
+			pallet_package::Error::<Test>::InvalidCarrier
+		);
+		assert_ok!(LogisticsModule::package_return(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+
+		assert_eq!(status(), Some(PackageStatus::Returned));
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), Some(40));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert_eq!(Balances::free_balance(CLIENT), 960);
+		assert_eq!(Balances::free_balance(CARRIER), 1_040);
+		assert_noop!(
+			LogisticsModule::package_return(RuntimeOrigin::signed(CARRIER), PACKAGE_ID, CLIENT),
+			pallet_package::Error::<Test>::PackageCannotBeReturned
+		);
+	});
+}
+
+#[test]
+fn package_report_lost_releases_reserves_without_payment() {
+	build_and_execute(|| {
+		collected_package();
+
+		assert_ok!(LogisticsModule::package_report_lost(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+
+		assert_eq!(status(), Some(PackageStatus::Lost));
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), None);
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert_eq!(Balances::free_balance(CLIENT), 1_000);
+		assert_eq!(Balances::free_balance(CARRIER), 1_000);
+		assert_noop!(
+			LogisticsModule::package_report_lost(
+				RuntimeOrigin::signed(CARRIER),
+				PACKAGE_ID,
+				CLIENT
+			),
+			pallet_package::Error::<Test>::PackageCannotBeReportedLost
+		);
+	});
+}
+
+#[test]
+fn package_expire_releases_ernest_deposit() {
+	build_and_execute(|| {
+		create_package();
+
+		System::set_block_number(100);
+		assert_noop!(
+			LogisticsModule::package_expire(RuntimeOrigin::signed(NOBODY), CLIENT, PACKAGE_ID),
+			pallet_package::Error::<Test>::PackageNotExpired
+		);
+
+		// Anyone can expire a package once it has waited long enough
+		System::set_block_number(101);
+		assert_ok!(LogisticsModule::package_expire(
+			RuntimeOrigin::signed(NOBODY),
+			CLIENT,
+			PACKAGE_ID
+		));
+
+		assert_eq!(status(), Some(PackageStatus::Expired));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert_noop!(
+			LogisticsModule::package_expire(RuntimeOrigin::signed(NOBODY), CLIENT, PACKAGE_ID),
+			pallet_package::Error::<Test>::PackageNotNew
+		);
+	});
+}
+
+#[test]
+fn quote_matches_final_fee() {
+	build_and_execute(|| {
+		collected_package();
+
+		assert_eq!(LogisticsModule::quote_final_fee(&CLIENT, &PACKAGE_ID, &CARRIER, 25), Some(70));
+		assert_eq!(LogisticsModule::quote_final_fee(&CLIENT, &PACKAGE_ID, &CARRIER, 0), None);
+
+		System::set_block_number(25);
+		assert_ok!(LogisticsModule::package_deliver(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER
+		));
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), Some(70));
+	});
+}
+
+#[test]
+fn on_idle_removes_concluded_packages_after_retention_period() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		propose(OTHER_CARRIER);
+		assert_ok!(LogisticsModule::package_cancel(RuntimeOrigin::signed(CLIENT), PACKAGE_ID));
+
+		// Open proposals keep their deposits until the package is removed
+		assert_eq!(Balances::reserved_balance(CARRIER), 5);
+
+		LogisticsModule::on_idle(10, Weight::MAX);
+		assert_eq!(status(), Some(PackageStatus::Cancelled));
+
+		LogisticsModule::on_idle(11, Weight::MAX);
+		assert_eq!(status(), None);
+		assert!(pallet_package::Manifests::<Test>::get(CLIENT, PACKAGE_ID).is_none());
+		assert!(LogisticsModule::proposals_for(&CLIENT, &PACKAGE_ID, 0, 10).is_empty());
+		assert_eq!(pallet_carrier::ProposalCount::<Test>::get(CLIENT, PACKAGE_ID), 0);
+		assert_eq!(Balances::reserved_balance(CARRIER), 0);
+		assert_eq!(Balances::reserved_balance(OTHER_CARRIER), 0);
+	});
+}
+
+#[test]
+fn on_idle_removes_settled_fees() {
+	build_and_execute(|| {
+		collected_package();
+		System::set_block_number(5);
+		assert_ok!(LogisticsModule::package_deliver(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER
+		));
+
+		LogisticsModule::on_idle(15, Weight::MAX);
+
+		assert_eq!(status(), None);
+		assert_eq!(SettledFees::<Test>::get(CLIENT, PACKAGE_ID), None);
+	});
+}
+
+#[test]
+fn on_idle_removes_insurance_cover() {
+	build_and_execute(|| {
+		assert_ok!(InsuranceModule::pool_deposit(RuntimeOrigin::signed(UNDERWRITER), 5_000));
+		create_package();
+		propose(CARRIER);
+		assert_ok!(LogisticsModule::package_assign(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER,
+			Some(500)
+		));
+		assert_ok!(LogisticsModule::package_collect(
+			RuntimeOrigin::signed(CARRIER),
+			PACKAGE_ID,
+			CLIENT
+		));
+		assert_ok!(LogisticsModule::package_deliver(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER
+		));
+
+		LogisticsModule::on_idle(11, Weight::MAX);
+
+		assert_eq!(status(), None);
+		assert!(InsuranceModule::get_policy(CLIENT, PACKAGE_ID).is_none());
+		assert_eq!(InsuranceModule::active_cover(), 0);
+	});
+}
+
+#[test]
+fn on_idle_does_nothing_without_remaining_weight() {
+	build_and_execute(|| {
+		create_package();
+		assert_ok!(LogisticsModule::package_cancel(RuntimeOrigin::signed(CLIENT), PACKAGE_ID));
+
+		assert_eq!(LogisticsModule::on_idle(100, Weight::zero()), Weight::zero());
+		assert_eq!(status(), Some(PackageStatus::Cancelled));
+	});
+}
+
+#[test]
+fn every_reserve_is_released_once_packages_conclude() {
+	build_and_execute(|| {
+		collected_package();
+		System::set_block_number(10);
+		assert_ok!(LogisticsModule::package_deliver(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			CARRIER
+		));
+
+		for account in [CLIENT, CARRIER, OTHER_CARRIER, THIRD_CARRIER] {
+			assert_eq!(Balances::reserved_balance(account), 0);
+		}
+		// Fees only move between client and carrier
+		assert_eq!(Balances::free_balance(CLIENT) + Balances::free_balance(CARRIER), 2_000);
+	});
+}
+
+#[test]
+fn set_parameter_updates_marketplace_parameters() {
+	build_and_execute(|| {
+		let parameter = MarketplaceParameter::PackageExpiry(200);
+
+		assert_ok!(LogisticsModule::set_parameter(RuntimeOrigin::root(), parameter.clone()));
+
+		assert_eq!(Parameters::<Test>::get().package_expiry, 200);
+		assert_eq!(<Test as pallet_package::Config>::PackageExpiry::get(), 200);
+		System::assert_last_event(Event::ParameterSet { parameter }.into());
+	});
+}
+
+#[test]
+fn set_parameter_requires_parameters_origin() {
+	build_and_execute(|| {
+		assert_noop!(
+			LogisticsModule::set_parameter(
+				RuntimeOrigin::signed(CLIENT),
+				MarketplaceParameter::ErnestDeposit(20)
+			),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn set_parameter_rejects_values_out_of_bounds() {
+	build_and_execute(|| {
+		assert_noop!(
+			LogisticsModule::set_parameter(
+				RuntimeOrigin::root(),
+				MarketplaceParameter::ErnestDeposit(101)
+			),
+			Error::<Test>::ParameterOutOfBounds
+		);
+		assert_noop!(
+			LogisticsModule::set_parameter(
+				RuntimeOrigin::root(),
+				MarketplaceParameter::InsuranceBaseRate(Permill::from_percent(11))
+			),
+			Error::<Test>::ParameterOutOfBounds
+		);
+	});
+}
+
+#[test]
+fn changed_ernest_deposit_releases_the_amount_reserved() {
+	build_and_execute(|| {
+		create_package();
+		assert_ok!(LogisticsModule::set_parameter(
+			RuntimeOrigin::root(),
+			MarketplaceParameter::ErnestDeposit(30)
+		));
+
+		// The open package keeps the deposit it was created with
+		assert_ok!(LogisticsModule::package_cancel(RuntimeOrigin::signed(CLIENT), PACKAGE_ID));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+
+		assert_ok!(LogisticsModule::package_create(
+			RuntimeOrigin::signed(CLIENT),
+			Some([2u8; 32]),
+			b"filled.count.soap".to_vec(),
+			b"index.home.raft".to_vec(),
+			b"Books".to_vec(),
+			10,
+			20,
+			30,
+			5,
+			false,
+			false,
+			500,
+		));
+		assert_eq!(Balances::reserved_balance(CLIENT), 30);
+	});
+}
+
+#[test]
+fn clients_tag_packages_with_metadata_and_references() {
+	build_and_execute(|| {
+		create_package();
+
+		assert_ok!(LogisticsModule::package_set_metadata(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			b"cost-centre".to_vec(),
+			b"42".to_vec(),
+		));
+		assert_ok!(LogisticsModule::package_set_reference(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			Some(b"SO-1001".to_vec()),
+		));
+		assert_eq!(LogisticsModule::package_by_reference(&CLIENT, b"SO-1001"), Some(PACKAGE_ID));
+		assert_eq!(
+			LogisticsModule::metadata(&CLIENT, &PACKAGE_ID),
+			vec![(b"cost-centre".to_vec(), b"42".to_vec())]
+		);
+		// Ernest deposit plus one per byte of metadata and reference
+		assert_eq!(Balances::reserved_balance(CLIENT), 10 + 13 + 7);
+
+		// Only the client's own packages can be tagged
+		assert_noop!(
+			LogisticsModule::package_set_metadata(
+				RuntimeOrigin::signed(CARRIER),
+				PACKAGE_ID,
+				b"po".to_vec(),
+				b"1".to_vec(),
+			),
+			pallet_package::Error::<Test>::PackageDoesNotExist
+		);
+
+		assert_ok!(LogisticsModule::package_clear_metadata(
+			RuntimeOrigin::signed(CLIENT),
+			PACKAGE_ID,
+			b"cost-centre".to_vec(),
+		));
+		assert!(LogisticsModule::metadata(&CLIENT, &PACKAGE_ID).is_empty());
+		assert_eq!(Balances::reserved_balance(CLIENT), 10 + 7);
+
+		// Metadata outlives the package's conclusion until it is pruned
+		assert_ok!(LogisticsModule::package_cancel(RuntimeOrigin::signed(CLIENT), PACKAGE_ID));
+		assert_eq!(Balances::reserved_balance(CLIENT), 7);
+		assert_eq!(LogisticsModule::package_by_reference(&CLIENT, b"SO-1001"), Some(PACKAGE_ID));
+	});
+}
+
+fn name(name: &str) -> Name {
+	name.as_bytes().to_vec().try_into().unwrap()
+}
+
+#[test]
+fn paused_call_is_filtered_until_unpaused() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		let assign_call = RuntimeCall::LogisticsModule(crate::Call::package_assign {
+			package_id: PACKAGE_ID,
+			carrier: CARRIER,
+			insured_value: None,
+		});
+
+		assert_ok!(LogisticsModule::pause(
+			RuntimeOrigin::root(),
+			name("LogisticsModule"),
+			Some(name("package_assign"))
+		));
+		System::assert_last_event(
+			Event::Paused {
+				pallet_name: name("LogisticsModule"),
+				call_name: Some(name("package_assign")),
+			}
+			.into(),
+		);
+		assert_noop!(
+			assign_call.clone().dispatch(RuntimeOrigin::signed(CLIENT)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+
+		assert_ok!(LogisticsModule::unpause(
+			RuntimeOrigin::root(),
+			name("LogisticsModule"),
+			Some(name("package_assign"))
+		));
+		assert_ok!(assign_call.dispatch(RuntimeOrigin::signed(CLIENT)));
+		assert_eq!(status(), Some(PackageStatus::Assigned));
+	});
+}
+
+#[test]
+fn paused_pallet_still_allows_safe_exits() {
+	build_and_execute(|| {
+		create_package();
+		propose(CARRIER);
+		assert_ok!(LogisticsModule::pause(RuntimeOrigin::root(), name("LogisticsModule"), None));
+
+		assert_noop!(
+			RuntimeCall::LogisticsModule(crate::Call::package_deliver {
+				package_id: PACKAGE_ID,
+				carrier: CARRIER,
+			})
+			.dispatch(RuntimeOrigin::signed(CLIENT)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+
+		assert_ok!(RuntimeCall::LogisticsModule(crate::Call::proposal_withdraw {
+			client: CLIENT,
+			package_id: PACKAGE_ID,
+		})
+		.dispatch(RuntimeOrigin::signed(CARRIER)));
+		assert_ok!(RuntimeCall::LogisticsModule(crate::Call::package_cancel {
+			package_id: PACKAGE_ID
+		})
+		.dispatch(RuntimeOrigin::signed(CLIENT)));
+		assert_eq!(status(), Some(PackageStatus::Cancelled));
+	});
+}
+
+#[test]
+fn pause_only_accepts_marketplace_calls() {
+	build_and_execute(|| {
+		assert_noop!(
+			LogisticsModule::pause(RuntimeOrigin::signed(CLIENT), name("LogisticsModule"), None),
+			BadOrigin
+		);
+		assert_noop!(
+			LogisticsModule::pause(RuntimeOrigin::root(), name("Balances"), None),
+			Error::<Test>::NotPausable
+		);
+		// Packages and proposals only change through this pallet's calls
+		assert_noop!(
+			LogisticsModule::pause(RuntimeOrigin::root(), name("PackageModule"), None),
+			Error::<Test>::NotPausable
+		);
+		assert_noop!(
+			LogisticsModule::pause(
+				RuntimeOrigin::root(),
+				name("LogisticsModule"),
+				Some(name("proposal_accept"))
+			),
+			Error::<Test>::NotPausable
+		);
+		assert_noop!(
+			LogisticsModule::pause(
+				RuntimeOrigin::root(),
+				name("LogisticsModule"),
+				Some(name("unpause"))
+			),
+			Error::<Test>::NotPausable
+		);
+		assert_noop!(
+			LogisticsModule::unpause(RuntimeOrigin::root(), name("LogisticsModule"), None),
+			Error::<Test>::NotPaused
+		);
+
+		assert_ok!(LogisticsModule::pause(RuntimeOrigin::root(), name("LogisticsModule"), None));
+		assert_noop!(
+			LogisticsModule::pause(RuntimeOrigin::root(), name("LogisticsModule"), None),
+			Error::<Test>::AlreadyPaused
+		);
+	});
+}
+
+#[test]
+fn pausing_the_pallet_never_pauses_unpausing() {
+	build_and_execute(|| {
+		assert_ok!(LogisticsModule::pause(RuntimeOrigin::root(), name("LogisticsModule"), None));
+
+		assert!(LogisticsModule::is_paused("LogisticsModule", "package_create"));
+		assert!(!LogisticsModule::is_paused("LogisticsModule", "unpause"));
+		assert!(!LogisticsModule::is_paused("PackageModule", "package_create"));
+	});
+}
+
+#[test]
+fn calls_of_other_pallets_are_never_paused() {
+	build_and_execute(|| {
+		// Even an entry for another pallet left in storage does not pause its calls
+		PausedCalls::<Test>::insert(name("Balances"), None::<Name>, ());
+		assert!(!LogisticsModule::is_paused("Balances", "transfer"));
+		PausedCalls::<Test>::remove(name("Balances"), None::<Name>);
+	});
+}
+
+#[test]
+fn try_state_reports_overflowing_reserved_funds() {
+	new_test_ext().execute_with(|| {
+		create_package();
+		pallet_package::MetadataDeposits::<Test>::insert(CLIENT, PACKAGE_ID, u64::MAX);
+
+		// The metadata deposit plus the ernest deposit no longer fits in a balance
+		assert_eq!(
+			LogisticsModule::do_try_state(),
+			Err("Funds required to be reserved overflow the balance type".into())
+		);
+	});
+}
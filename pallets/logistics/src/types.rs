@@ -1 +1,137 @@
-pub type PackageId = [u8; 32];
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	sp_runtime::{Permill, RuntimeDebug},
+	traits::ConstU32,
+	BoundedVec,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_carrier::types::Proposal;
+use pallet_package::types::{Manifest, Package};
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+
+use crate::{BalanceOf, Config};
+
+pub use logistics_primitives::PackageId;
+
+/// Prefix of the offchain storage keys under which concluded packages are archived
+pub const ARCHIVE_PREFIX: &[u8] = b"logistics::archive";
+
+/// Offchain storage key under which a concluded package is archived
+pub fn archive_key<AccountId: Encode>(client: &AccountId, package_id: &PackageId) -> Vec<u8> {
+	(ARCHIVE_PREFIX, client, package_id).encode()
+}
+
+/// Longest pallet or call name which can be paused
+pub const MAX_NAME_LENGTH: u32 = 64;
+
+/// Name of a pallet or of one of its calls, as reported by `GetCallMetadata`
+pub type Name = BoundedVec<u8, ConstU32<MAX_NAME_LENGTH>>;
+
+/// Everything known about a package at the point it is removed from storage
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ArchivedPackage<T: Config> {
+	pub package: Package<T>,
+	pub manifest: Option<Manifest<T>>,
+	pub proposal: Option<Proposal<T>>,
+	pub final_fee: Option<BalanceOf<T>>,
+}
+
+/// Marketplace parameters which governance can change without a runtime upgrade
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct MarketplaceParameters<Balance, BlockNumber> {
+	/// Reserved from the client for every package they create
+	pub ernest_deposit: Balance,
+	/// Reserved from the carrier for every open proposal they make
+	pub proposal_deposit: Balance,
+	/// Number of blocks a concluded package is kept before it is removed
+	pub concluded_retention_period: BlockNumber,
+	/// Number of blocks a package can wait for a carrier before it may be expired
+	pub package_expiry: BlockNumber,
+	/// Premium charged on the insured value of every shipment
+	pub insurance_base_rate: Permill,
+	/// Additional premium for shipments containing hazardous materials
+	pub hazardous_materials_surcharge: Permill,
+	/// Additional premium for shipments which must be climate controlled
+	pub climate_controlled_surcharge: Permill,
+	/// Share of each delivered package's final fee kept by the marketplace
+	pub commission_rate: Permill,
+}
+
+pub type MarketplaceParametersOf<T> = MarketplaceParameters<BalanceOf<T>, BlockNumberFor<T>>;
+
+/// A new value for one of the [`MarketplaceParameters`]
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum MarketplaceParameter<Balance, BlockNumber> {
+	ErnestDeposit(Balance),
+	ProposalDeposit(Balance),
+	ConcludedRetentionPeriod(BlockNumber),
+	PackageExpiry(BlockNumber),
+	InsuranceBaseRate(Permill),
+	HazardousMaterialsSurcharge(Permill),
+	ClimateControlledSurcharge(Permill),
+	CommissionRate(Permill),
+}
+
+pub type MarketplaceParameterOf<T> = MarketplaceParameter<BalanceOf<T>, BlockNumberFor<T>>;
+
+impl<Balance: PartialOrd, BlockNumber: PartialOrd> MarketplaceParameter<Balance, BlockNumber> {
+	/// Whether the new value lies within the `minimum` and `maximum` values of the parameter
+	pub fn is_within(
+		&self,
+		minimum: &MarketplaceParameters<Balance, BlockNumber>,
+		maximum: &MarketplaceParameters<Balance, BlockNumber>,
+	) -> bool {
+		fn within<V: PartialOrd>(value: &V, minimum: &V, maximum: &V) -> bool {
+			minimum <= value && value <= maximum
+		}
+
+		match self {
+			Self::ErnestDeposit(value) =>
+				within(value, &minimum.ernest_deposit, &maximum.ernest_deposit),
+			Self::ProposalDeposit(value) =>
+				within(value, &minimum.proposal_deposit, &maximum.proposal_deposit),
+			Self::ConcludedRetentionPeriod(value) => within(
+				value,
+				&minimum.concluded_retention_period,
+				&maximum.concluded_retention_period,
+			),
+			Self::PackageExpiry(value) =>
+				within(value, &minimum.package_expiry, &maximum.package_expiry),
+			Self::InsuranceBaseRate(value) =>
+				within(value, &minimum.insurance_base_rate, &maximum.insurance_base_rate),
+			Self::HazardousMaterialsSurcharge(value) => within(
+				value,
+				&minimum.hazardous_materials_surcharge,
+				&maximum.hazardous_materials_surcharge,
+			),
+			Self::ClimateControlledSurcharge(value) => within(
+				value,
+				&minimum.climate_controlled_surcharge,
+				&maximum.climate_controlled_surcharge,
+			),
+			Self::CommissionRate(value) =>
+				within(value, &minimum.commission_rate, &maximum.commission_rate),
+		}
+	}
+}
+
+impl<Balance, BlockNumber> MarketplaceParameters<Balance, BlockNumber> {
+	/// Replace the value of a single parameter
+	pub fn set(&mut self, parameter: MarketplaceParameter<Balance, BlockNumber>) {
+		match parameter {
+			MarketplaceParameter::ErnestDeposit(value) => self.ernest_deposit = value,
+			MarketplaceParameter::ProposalDeposit(value) => self.proposal_deposit = value,
+			MarketplaceParameter::ConcludedRetentionPeriod(value) =>
+				self.concluded_retention_period = value,
+			MarketplaceParameter::PackageExpiry(value) => self.package_expiry = value,
+			MarketplaceParameter::InsuranceBaseRate(value) => self.insurance_base_rate = value,
+			MarketplaceParameter::HazardousMaterialsSurcharge(value) =>
+				self.hazardous_materials_surcharge = value,
+			MarketplaceParameter::ClimateControlledSurcharge(value) =>
+				self.climate_controlled_surcharge = value,
+			MarketplaceParameter::CommissionRate(value) => self.commission_rate = value,
+		}
+	}
+}
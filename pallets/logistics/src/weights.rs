@@ -0,0 +1,546 @@
+//! Weights for pallet_logistics
+//!
+//! THESE ARE HAND-WRITTEN ESTIMATES, NOT BENCHMARK RESULTS. Storage access counts are
+//! exact, execution times are placeholders. Regenerate on reference hardware with
+//! `scripts/benchmark.sh` before relying on them.
+
+// Command to regenerate:
+// ./target/release/node-template
+// benchmark
+// pallet
+// --chain
+// dev
+// --pallet
+// pallet_logistics
+// --extrinsic
+// *
+// --steps=50
+// --repeat=20
+// --wasm-execution=compiled
+// --output
+// pallets/logistics/src/weights.rs
+// --template
+// .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_logistics.
+pub trait WeightInfo {
+	fn package_assign(p: u32, ) -> Weight;
+	fn package_cancel() -> Weight;
+	fn package_deliver() -> Weight;
+	fn proposal_create() -> Weight;
+	fn package_create() -> Weight;
+	fn package_collect() -> Weight;
+	fn package_hold() -> Weight;
+	fn package_release() -> Weight;
+	fn package_dispute() -> Weight;
+	fn package_return() -> Weight;
+	fn package_report_lost() -> Weight;
+	fn package_expire() -> Weight;
+	fn prune_concluded(p: u32, ) -> Weight;
+	fn on_idle(c: u32, ) -> Weight;
+	fn set_parameter() -> Weight;
+	fn pause() -> Weight;
+	fn unpause() -> Weight;
+	fn proposal_withdraw() -> Weight;
+	fn package_set_metadata() -> Weight;
+	fn package_clear_metadata() -> Weight;
+	fn package_set_reference() -> Weight;
+	fn package_set_line_items(l: u32, ) -> Weight;
+	fn package_deliver_partial(l: u32, ) -> Weight;
+	fn claim_missing_items() -> Weight;
+	fn package_update(p: u32, ) -> Weight;
+	fn proposal_confirm() -> Weight;
+}
+
+/// Weights for pallet_logistics using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: CarrierModule Proposals (r:p+3 w:p)
+	/// Storage: System Account (r:p+3 w:p+3)
+	/// Storage: PackageModule CarrierPackages (r:1 w:1)
+	/// Storage: PackageModule Manifests (r:1 w:0)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: InsuranceModule ActiveCover (r:1 w:1)
+	/// Storage: LogisticsModule Parameters (r:1 w:0)
+	/// The range of component `p` is `[1, 100]`.
+	fn package_assign(p: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 8_000)
+			.saturating_add(Weight::from_parts(15_000_000, 5_200).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(p.into())))
+	}
+	/// Storage: PackageModule Packages (r:1 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	/// Storage: PackageModule ConcludedTail (r:1 w:1)
+	/// Storage: PackageModule ConcludedPackages (r:0 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: PackageModule ErnestDeposits (r:1 w:1)
+	fn package_cancel() -> Weight {
+		Weight::from_parts(35_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	/// Storage: PackageModule CarrierPackages (r:1 w:1)
+	/// Storage: PackageModule ConcludedTail (r:1 w:1)
+	/// Storage: PackageModule ConcludedPackages (r:0 w:1)
+	/// Storage: CarrierModule Proposals (r:3 w:0)
+	/// Storage: LogisticsModule Parameters (r:1 w:0)
+	/// Storage: System Account (r:3 w:3)
+	/// Storage: LogisticsModule SettledFees (r:0 w:1)
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: PackageModule ErnestDeposits (r:1 w:1)
+	fn package_deliver() -> Weight {
+		Weight::from_parts(70_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(14_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:0)
+	/// Storage: CarrierModule Proposals (r:1 w:1)
+	/// Storage: CarrierModule ProposalCount (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: LogisticsModule Parameters (r:1 w:0)
+	fn proposal_create() -> Weight {
+		Weight::from_parts(33_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: PackageModule Manifests (r:0 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:1)
+	/// Storage: LogisticsModule Parameters (r:1 w:0)
+	/// Storage: PackageModule ErnestDeposits (r:0 w:1)
+	/// Storage: PackageModule PackageClients (r:1 w:1)
+	/// Storage: PackageModule PackageNonce (r:1 w:1)
+	fn package_create() -> Weight {
+		Weight::from_parts(45_000_000, 6_500)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	fn package_collect() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	fn package_hold() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	fn package_release() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	fn package_dispute() -> Weight {
+		Weight::from_parts(28_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: PackageModule Packages (r:3 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	/// Storage: PackageModule CarrierPackages (r:1 w:1)
+	/// Storage: PackageModule ConcludedTail (r:1 w:1)
+	/// Storage: PackageModule ConcludedPackages (r:0 w:1)
+	/// Storage: CarrierModule Proposals (r:2 w:0)
+	/// Storage: System Account (r:2 w:2)
+	/// Storage: LogisticsModule SettledFees (r:0 w:1)
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: PackageModule ErnestDeposits (r:1 w:1)
+	fn package_return() -> Weight {
+		Weight::from_parts(70_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().writes(13_u64))
+	}
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	/// Storage: PackageModule CarrierPackages (r:1 w:1)
+	/// Storage: PackageModule ConcludedTail (r:1 w:1)
+	/// Storage: PackageModule ConcludedPackages (r:0 w:1)
+	/// Storage: CarrierModule Proposals (r:1 w:0)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: PackageModule ErnestDeposits (r:1 w:1)
+	fn package_report_lost() -> Weight {
+		Weight::from_parts(55_000_000, 8_000)
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	/// Storage: PackageModule ConcludedTail (r:1 w:1)
+	/// Storage: PackageModule ConcludedPackages (r:0 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: LogisticsModule Parameters (r:1 w:0)
+	/// Storage: PackageModule ErnestDeposits (r:1 w:1)
+	fn package_expire() -> Weight {
+		Weight::from_parts(38_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: PackageModule ConcludedHead (r:1 w:1)
+	/// Storage: PackageModule ConcludedPackages (r:1 w:1)
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: PackageModule Manifests (r:1 w:1)
+	/// Storage: PackageModule Timelines (r:0 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:1)
+	/// Storage: CarrierModule Proposals (r:p w:p)
+	/// Storage: CarrierModule ProposalCount (r:0 w:1)
+	/// Storage: System Account (r:p w:p)
+	/// Storage: LogisticsModule SettledFees (r:1 w:1)
+	/// Storage: LogisticsModule Parameters (r:1 w:0)
+	/// Storage: PackageModule ErnestDeposits (r:1 w:1)
+	/// Storage: PackageModule PackageClients (r:1 w:1)
+	/// Storage: PackageModule PackageMetadata (r:0 w:1)
+	/// Storage: PackageModule ExternalReferences (r:1 w:1)
+	/// Storage: PackageModule PackagesByReference (r:0 w:1)
+	/// Storage: PackageModule MetadataDeposits (r:1 w:1)
+	/// Storage: PackageModule PackageLineItems (r:0 w:1)
+	/// Storage: PackageModule PackageReceived (r:0 w:1)
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: InsuranceModule ActiveCover (r:1 w:1)
+	/// Storage: InsuranceModule Claims (r:0 w:1)
+	/// The range of component `p` is `[1, 100]`.
+	fn prune_concluded(p: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 6_000)
+			.saturating_add(Weight::from_parts(8_000_000, 2_600).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(13_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(19_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(p.into())))
+	}
+	/// Storage: PackageModule ConcludedHead (r:c+1 w:c)
+	/// Storage: PackageModule ConcludedPackages (r:c+1 w:c)
+	/// Storage: PackageModule Packages (r:2c w:c)
+	/// Storage: PackageModule Manifests (r:c w:c)
+	/// Storage: PackageModule Timelines (r:0 w:c)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:c)
+	/// Storage: CarrierModule Proposals (r:c w:c)
+	/// Storage: LogisticsModule SettledFees (r:c w:c)
+	/// Storage: LogisticsModule Parameters (r:c w:0)
+	/// Storage: PackageModule ErnestDeposits (r:c w:c)
+	/// Storage: PackageModule PackageClients (r:c w:c)
+	/// Storage: PackageModule PackageMetadata (r:0 w:c)
+	/// Storage: PackageModule ExternalReferences (r:c w:c)
+	/// Storage: PackageModule PackagesByReference (r:0 w:c)
+	/// Storage: PackageModule MetadataDeposits (r:c w:c)
+	/// Storage: PackageModule PackageLineItems (r:0 w:c)
+	/// Storage: PackageModule PackageReceived (r:0 w:c)
+	/// Storage: InsuranceModule Policies (r:c w:c)
+	/// Storage: InsuranceModule ActiveCover (r:c w:c)
+	/// Storage: InsuranceModule Claims (r:0 w:c)
+	/// The range of component `c` is `[0, 64]`.
+	fn on_idle(c: u32, ) -> Weight {
+		Weight::from_parts(5_000_000, 1_500)
+			.saturating_add(Weight::from_parts(45_000_000, 6000).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((14_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes((19_u64).saturating_mul(c.into())))
+	}
+	/// Storage: LogisticsModule Parameters (r:1 w:1)
+	fn set_parameter() -> Weight {
+		Weight::from_parts(12_000_000, 1_600)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: LogisticsModule PausedCalls (r:1 w:1)
+	fn pause() -> Weight {
+		Weight::from_parts(14_000_000, 3_500)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: LogisticsModule PausedCalls (r:1 w:1)
+	fn unpause() -> Weight {
+		Weight::from_parts(14_000_000, 3_500)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: CarrierModule Proposals (r:1 w:1)
+	/// Storage: CarrierModule ProposalCount (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn proposal_withdraw() -> Weight {
+		Weight::from_parts(28_000_000, 3_600)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:0)
+	/// Storage: PackageModule PackageMetadata (r:1 w:1)
+	/// Storage: PackageModule ExternalReferences (r:1 w:0)
+	/// Storage: PackageModule MetadataDeposits (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn package_set_metadata() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: PackageModule PackageMetadata (r:1 w:1)
+	/// Storage: PackageModule ExternalReferences (r:1 w:0)
+	/// Storage: PackageModule MetadataDeposits (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn package_clear_metadata() -> Weight {
+		Weight::from_parts(28_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:0)
+	/// Storage: PackageModule PackagesByReference (r:1 w:2)
+	/// Storage: PackageModule ExternalReferences (r:2 w:1)
+	/// Storage: PackageModule PackageMetadata (r:1 w:0)
+	/// Storage: PackageModule MetadataDeposits (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn package_set_reference() -> Weight {
+		Weight::from_parts(32_000_000, 5_000)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:0)
+	/// Storage: PackageModule Manifests (r:1 w:1)
+	/// Storage: PackageModule PackageLineItems (r:0 w:1)
+	/// The range of component `l` is `[0, 32]`.
+	fn package_set_line_items(l: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 4_000)
+			.saturating_add(Weight::from_parts(500_000, 300).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: PackageModule PackageLineItems (r:1 w:0)
+	/// Storage: PackageModule PackageReceived (r:0 w:1)
+	/// Storage: PackageModule Packages (r:2 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: PackageModule Timelines (r:1 w:1)
+	/// Storage: PackageModule PackagesByStatus (r:0 w:2)
+	/// Storage: PackageModule CarrierPackages (r:1 w:1)
+	/// Storage: PackageModule ConcludedTail (r:1 w:1)
+	/// Storage: PackageModule ConcludedPackages (r:0 w:1)
+	/// Storage: CarrierModule Proposals (r:3 w:0)
+	/// Storage: LogisticsModule Parameters (r:1 w:0)
+	/// Storage: System Account (r:3 w:3)
+	/// Storage: LogisticsModule SettledFees (r:0 w:1)
+	/// Storage: InsuranceModule Policies (r:1 w:1)
+	/// Storage: PackageModule ErnestDeposits (r:1 w:1)
+	/// The range of component `l` is `[1, 32]`.
+	fn package_deliver_partial(l: u32, ) -> Weight {
+		Weight::from_parts(75_000_000, 9_000)
+			.saturating_add(Weight::from_parts(300_000, 300).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(17_u64))
+			.saturating_add(T::DbWeight::get().writes(15_u64))
+	}
+	/// Storage: PackageModule PackageReceived (r:1 w:0)
+	/// Storage: PackageModule PackageLineItems (r:1 w:0)
+	/// Storage: InsuranceModule Policies (r:2 w:0)
+	/// Storage: InsuranceModule Claims (r:1 w:1)
+	fn claim_missing_items() -> Weight {
+		Weight::from_parts(30_000_000, 6_000)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: PackageModule Packages (r:1 w:1)
+	/// Storage: PackageModule PackageLineItems (r:1 w:0)
+	/// Storage: PackageModule Manifests (r:0 w:1)
+	/// Storage: CarrierModule Proposals (r:p w:p)
+	/// The range of component `p` is `[0, 100]`.
+	fn package_update(p: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(Weight::from_parts(8_000_000, 2_600).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(p.into())))
+	}
+	/// Storage: CarrierModule Proposals (r:1 w:1)
+	fn proposal_confirm() -> Weight {
+		Weight::from_parts(18_000_000, 3_600)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn package_assign(p: u32, ) -> Weight {
+		Weight::from_parts(60_000_000, 8_000)
+			.saturating_add(Weight::from_parts(15_000_000, 5_200).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(p.into())))
+	}
+	fn package_cancel() -> Weight {
+		Weight::from_parts(35_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	fn package_deliver() -> Weight {
+		Weight::from_parts(70_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(14_u64))
+	}
+	fn proposal_create() -> Weight {
+		Weight::from_parts(33_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn package_create() -> Weight {
+		Weight::from_parts(45_000_000, 6_500)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	fn package_collect() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn package_hold() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn package_release() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn package_dispute() -> Weight {
+		Weight::from_parts(28_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn package_return() -> Weight {
+		Weight::from_parts(70_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(15_u64))
+			.saturating_add(RocksDbWeight::get().writes(13_u64))
+	}
+	fn package_report_lost() -> Weight {
+		Weight::from_parts(55_000_000, 8_000)
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	fn package_expire() -> Weight {
+		Weight::from_parts(38_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	fn prune_concluded(p: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 6_000)
+			.saturating_add(Weight::from_parts(8_000_000, 2_600).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(13_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(19_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(p.into())))
+	}
+	fn on_idle(c: u32, ) -> Weight {
+		Weight::from_parts(5_000_000, 1_500)
+			.saturating_add(Weight::from_parts(45_000_000, 6000).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((14_u64).saturating_mul(c.into())))
+			.saturating_add(RocksDbWeight::get().writes((19_u64).saturating_mul(c.into())))
+	}
+	fn set_parameter() -> Weight {
+		Weight::from_parts(12_000_000, 1_600)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn pause() -> Weight {
+		Weight::from_parts(14_000_000, 3_500)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn unpause() -> Weight {
+		Weight::from_parts(14_000_000, 3_500)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn proposal_withdraw() -> Weight {
+		Weight::from_parts(28_000_000, 3_600)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn package_set_metadata() -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn package_clear_metadata() -> Weight {
+		Weight::from_parts(28_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn package_set_reference() -> Weight {
+		Weight::from_parts(32_000_000, 5_000)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	fn package_set_line_items(l: u32, ) -> Weight {
+		Weight::from_parts(20_000_000, 4_000)
+			.saturating_add(Weight::from_parts(500_000, 300).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn package_deliver_partial(l: u32, ) -> Weight {
+		Weight::from_parts(75_000_000, 9_000)
+			.saturating_add(Weight::from_parts(300_000, 300).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(17_u64))
+			.saturating_add(RocksDbWeight::get().writes(15_u64))
+	}
+	fn claim_missing_items() -> Weight {
+		Weight::from_parts(30_000_000, 6_000)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn package_update(p: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 5_000)
+			.saturating_add(Weight::from_parts(8_000_000, 2_600).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(p.into())))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(p.into())))
+	}
+	fn proposal_confirm() -> Weight {
+		Weight::from_parts(18_000_000, 3_600)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
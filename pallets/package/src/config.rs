@@ -3,9 +3,9 @@ use frame_support::pallet_macros::*;
 #[pallet_section]
 mod config {
 
-	use frame_support::traits::{Currency, OnUnbalanced, ReservableCurrency};
+	use frame_support::traits::{Currency, OnUnbalanced, ReservableCurrency, UnixTime};
 
-	type BalanceOf<T> =
+	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 	type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
 		<T as frame_system::Config>::AccountId,
@@ -30,8 +30,55 @@ mod config {
 		#[pallet::constant]
 		type DescriptionMaxLength: Get<u32>;
 
-		/// Max Vec length for concluded packages
+		/// Max number of concluded packages removed in a single block
 		#[pallet::constant]
 		type MaxConcludedPackages: Get<u32>;
+
+		/// Number of blocks a concluded package is kept before it is removed
+		#[pallet::constant]
+		type ConcludedRetentionPeriod: Get<BlockNumberFor<Self>>;
+
+		/// Number of blocks a package can wait for a carrier before it may be expired
+		#[pallet::constant]
+		type PackageExpiry: Get<BlockNumberFor<Self>>;
+
+		/// Source of the timestamps recorded in package timelines
+		type UnixTime: UnixTime;
+
+		/// Max number of status changes kept in a package's timeline
+		#[pallet::constant]
+		type MaxTimelineEntries: Get<u32>;
+
+		/// Max number of packages a carrier can be working on at once
+		#[pallet::constant]
+		type MaxCarrierPackages: Get<u32>;
+
+		/// Max number of metadata entries attached to a package
+		#[pallet::constant]
+		type MaxMetadataEntries: Get<u32>;
+
+		/// Max length of a metadata key
+		#[pallet::constant]
+		type MaxMetadataKeyLength: Get<u32>;
+
+		/// Max length of a metadata value
+		#[pallet::constant]
+		type MaxMetadataValueLength: Get<u32>;
+
+		/// Max length of the reference a client's own systems know a package by
+		#[pallet::constant]
+		type MaxReferenceLength: Get<u32>;
+
+		/// Reserved from the client for every byte of metadata and external reference
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// Max number of line items in a package's manifest
+		#[pallet::constant]
+		type MaxLineItems: Get<u32>;
+
+		/// Max length of a line item's SKU
+		#[pallet::constant]
+		type MaxSkuLength: Get<u32>;
 	}
 }
@@ -24,15 +24,47 @@ mod errors {
 		PackageInTransit,
 		/// Package is *not* In Transit
 		PackageNotInTransit,
+		/// Package is in a state which can not be marked as delivered
+		PackageCannotBeDelivered,
+		/// Package is in a state which can not be released back into transit
+		PackageCannotBeReleased,
+		/// Package is in a state which can not be disputed
+		PackageCannotBeDisputed,
+		/// Package is in a state which can not be returned to sender
+		PackageCannotBeReturned,
+		/// Package is in a state which can not be reported as lost
+		PackageCannotBeReportedLost,
+		/// Package has not been waiting for a carrier long enough to expire
+		PackageNotExpired,
 		/// Requested package is not valid for requested action
 		InvalidPackage,
 		/// Action attempted on package by carrier who is not assigned
 		InvalidCarrier,
+		/// Carrier is already working on the maximum number of packages
+		CarrierWorkloadFull,
 		/// Three Word Address format is invalid
 		InvalidThreeWordAddress,
 		/// Manifest Description is too long
 		InvalidDescription,
 		/// Not enough funds available to perform the requested action
 		InsufficientFunds,
+		/// Metadata key or value is too long
+		InvalidMetadata,
+		/// Package already has the maximum number of metadata entries
+		TooManyMetadataEntries,
+		/// Package has no metadata under that key
+		MetadataDoesNotExist,
+		/// External reference is too long
+		InvalidReference,
+		/// Another of the client's packages already has that external reference
+		ReferenceInUse,
+		/// Line item SKU is too long or its quantity is zero
+		InvalidLineItem,
+		/// Manifest already has the maximum number of line items
+		TooManyLineItems,
+		/// Package has no line items to confirm
+		NoLineItems,
+		/// Received quantities do not match the line items, or exceed the quantity shipped
+		InvalidReceivedQuantities,
 	}
 }
@@ -17,5 +17,34 @@ mod events {
 		PackageDelivered { client: T::AccountId, package: PackageId },
 		/// Package has been cancelled [Client Account ID, Package ID]
 		PackageCancelled { client: T::AccountId, package: PackageId },
+		/// Package has been put on hold by its carrier [Client ID, Package ID, Carrier ID]
+		PackageHeld { client: T::AccountId, package: PackageId, carrier: T::AccountId },
+		/// Package has been released back into transit [Client ID, Package ID]
+		PackageReleased { client: T::AccountId, package: PackageId },
+		/// Package has been disputed by its client [Client ID, Package ID]
+		PackageDisputed { client: T::AccountId, package: PackageId },
+		/// Package has been returned to sender [Client ID, Package ID, Carrier ID]
+		PackageReturned { client: T::AccountId, package: PackageId, carrier: T::AccountId },
+		/// Package has been reported lost [Client ID, Package ID, Carrier ID]
+		PackageLost { client: T::AccountId, package: PackageId, carrier: T::AccountId },
+		/// Package expired before being assigned to a carrier [Client ID, Package ID]
+		PackageExpired { client: T::AccountId, package: PackageId },
+		/// Metadata entry set on a package [Client ID, Package ID, Key]
+		MetadataSet { client: T::AccountId, package: PackageId, key: MetadataKey<T> },
+		/// Metadata entry removed from a package [Client ID, Package ID, Key]
+		MetadataCleared { client: T::AccountId, package: PackageId, key: MetadataKey<T> },
+		/// External reference of a package set or removed [Client ID, Package ID, Reference]
+		ExternalReferenceSet {
+			client: T::AccountId,
+			package: PackageId,
+			reference: Option<ExternalReference<T>>,
+		},
+		/// Line items of a package's manifest replaced [Client ID, Package ID, Number of items]
+		LineItemsSet { client: T::AccountId, package: PackageId, count: u32 },
+		/// Client confirmed which line items arrived [Client ID, Package ID, Share of value
+		/// received]
+		LineItemsReceived { client: T::AccountId, package: PackageId, received: Perbill },
+		/// Addresses or manifest of a new package amended [Client ID, Package ID]
+		PackageUpdated { client: T::AccountId, package: PackageId },
 	}
 }
@@ -2,25 +2,48 @@
 
 pub use pallet::*;
 
-mod calls;
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 mod config;
 mod errors;
 mod events;
+pub mod migrations;
+pub mod traits;
 pub mod types;
 
-use crate::types::*;
-use frame_support::{pallet_macros::*, pallet_prelude::*};
+use crate::{traits::*, types::*};
+use frame_support::{
+	pallet_macros::*,
+	pallet_prelude::*,
+	sp_runtime::{
+		traits::{Saturating, Zero},
+		Perbill, SaturatedConversion,
+	},
+	traits::{ReservableCurrency, UnixTime},
+};
 use frame_system::{self as system, pallet_prelude::*};
+use sp_io::hashing::blake2_256;
+use sp_std::vec::Vec;
+
+/// Mixed into every derived package ID so that it can not be mistaken for any other hash
+const PACKAGE_ID_PREFIX: &[u8] = b"logistics/package";
 
 #[import_section(events::events)]
 #[import_section(errors::errors)]
 #[import_section(config::config)]
-#[import_section(calls::calls)]
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -44,23 +67,581 @@ pub mod pallet {
 		Manifest<T>,
 	>;
 
+	/// Every package, grouped by its current status
+	#[pallet::storage]
+	pub type PackagesByStatus<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		PackageStatus,
+		Blake2_128Concat,
+		(T::AccountId, PackageId),
+		(),
+	>;
+
+	/// Ernest deposit reserved from the client when each package was created,
+	/// kept until it is released so that changes to `ErnestDeposit` never release the wrong amount
+	#[pallet::storage]
+	pub type ErnestDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Client of every package with a derived ID, so that it can be found from its ID alone.
+	/// IDs chosen by clients are only unique among their own packages and are not indexed.
+	#[pallet::storage]
+	pub type PackageClients<T: Config> = StorageMap<_, Blake2_128Concat, PackageId, T::AccountId>;
+
+	/// Line items of each package shipping a mix of items
+	#[pallet::storage]
+	pub type PackageLineItems<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		LineItems<T>,
+		ValueQuery,
+	>;
+
+	/// Quantity of each line item the client confirmed receiving on delivery
+	#[pallet::storage]
+	pub type PackageReceived<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		ReceivedQuantities<T>,
+	>;
+
+	/// Metadata the client has attached to each package
+	#[pallet::storage]
+	pub type PackageMetadata<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		Metadata<T>,
+		ValueQuery,
+	>;
+
+	/// External reference of each package which has one
+	#[pallet::storage]
+	pub type ExternalReferences<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		ExternalReference<T>,
+	>;
+
+	/// Package each client's external references refer to
+	#[pallet::storage]
+	pub type PackagesByReference<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		ExternalReference<T>,
+		PackageId,
+	>;
+
+	/// Deposit reserved from the client for the metadata and external reference of each package
+	#[pallet::storage]
+	pub type MetadataDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Number of package IDs derived so far, mixed into each derived ID
+	#[pallet::storage]
+	pub type PackageNonce<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Packages assigned to each carrier which have not concluded yet
+	#[pallet::storage]
+	pub type CarrierPackages<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId, // Carrier's account id
+		BoundedVec<(T::AccountId, PackageId), T::MaxCarrierPackages>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	pub type Timelines<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		PackageId,
+		BoundedVec<TimelineEntry<T::AccountId, BlockNumberFor<T>>, T::MaxTimelineEntries>,
+		ValueQuery,
+	>;
+
+	/// Queue of concluded packages awaiting removal, with the block they concluded in
 	#[pallet::storage]
 	pub type ConcludedPackages<T: Config> =
-		StorageValue<_, BoundedVec<(T::AccountId, PackageId), T::MaxConcludedPackages>>;
+		StorageMap<_, Twox64Concat, u64, (T::AccountId, PackageId, BlockNumberFor<T>)>;
+
+	/// Index of the next concluded package to be removed
+	#[pallet::storage]
+	pub type ConcludedHead<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Index at which the next concluded package will be queued
+	#[pallet::storage]
+	pub type ConcludedTail<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// Open packages waiting for a carrier. Each client's ernest deposit is reserved.
+		pub packages: Vec<GenesisPackage<T::AccountId, BalanceOf<T>>>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for (
+				client,
+				package_id,
+				pickup,
+				destination,
+				description,
+				(length, width, height),
+				weight,
+				declared_value,
+			) in &self.packages
+			{
+				let manifest = Manifest::new(
+					description.clone(),
+					Dimensions { length: *length, width: *width, height: *height },
+					*weight,
+					false,
+					false,
+					*declared_value,
+				)
+				.expect("genesis package descriptions are within the limit");
+
+				Pallet::<T>::create_package(
+					client,
+					Some(*package_id),
+					pickup.clone(),
+					destination.clone(),
+					manifest,
+				)
+				.expect("genesis packages are valid and their clients can pay the deposit");
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(
+			_n: BlockNumberFor<T>,
+		) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
 }
 
 impl<T: Config> Pallet<T> {
-	pub fn package_is_new(client: &T::AccountId, package_id: &PackageId) -> bool {
-		let package = Self::get_package(&client, &package_id);
-		package.is_some() && package.unwrap().status == PackageStatus::New
+	/// Set a manifest's weight and declared value to the totals of its line items
+	fn apply_line_item_totals(manifest: &mut Manifest<T>, line_items: &LineItems<T>) {
+		manifest.weight = line_items.iter().fold(0u32, |weight, item| {
+			weight.saturating_add(item.unit_weight.saturating_mul(item.quantity))
+		});
+		manifest.declared_value =
+			line_items.iter().fold(Zero::zero(), |value: BalanceOf<T>, item| {
+				value.saturating_add(item.value_of(item.quantity))
+			});
+	}
+
+	/// Derive a fresh package ID for `client` from a nonce and the current block
+	pub(crate) fn next_package_id(client: &T::AccountId) -> PackageId {
+		let nonce = PackageNonce::<T>::mutate(|nonce| {
+			let current = *nonce;
+			*nonce = nonce.wrapping_add(1);
+			current
+		});
+
+		(PACKAGE_ID_PREFIX, client, nonce, <system::Pallet<T>>::block_number())
+			.using_encoded(blake2_256)
+	}
+
+	/// Error returned when `action` is not permitted from a package's current status
+	pub(crate) fn transition_error(action: PackageAction) -> Error<T> {
+		match action {
+			PackageAction::Assign | PackageAction::Cancel | PackageAction::Expire =>
+				Error::<T>::PackageNotNew,
+			PackageAction::Collect => Error::<T>::PackageCannotBeCollected,
+			PackageAction::Hold => Error::<T>::PackageNotInTransit,
+			PackageAction::Release => Error::<T>::PackageCannotBeReleased,
+			PackageAction::Dispute => Error::<T>::PackageCannotBeDisputed,
+			PackageAction::Deliver => Error::<T>::PackageCannotBeDelivered,
+			PackageAction::Return => Error::<T>::PackageCannotBeReturned,
+			PackageAction::ReportLost => Error::<T>::PackageCannotBeReportedLost,
+		}
+	}
+
+	/// Move a package to its next status according to [`PACKAGE_TRANSITIONS`]
+	pub(crate) fn transition(
+		package: &mut Package<T>,
+		action: PackageAction,
+		actor: &T::AccountId,
+	) -> DispatchResult {
+		let previous = package.status;
+		let status = previous.next(action).ok_or(Self::transition_error(action))?;
+
+		if action == PackageAction::Assign {
+			if let Some(carrier) = &package.carrier {
+				CarrierPackages::<T>::try_mutate(carrier, |packages| {
+					packages.try_push((package.client.clone(), package.id))
+				})
+				.map_err(|_| Error::<T>::CarrierWorkloadFull)?;
+			}
+		}
+
+		package.status = status;
+
+		match action {
+			PackageAction::Collect =>
+				package.collected_on = Some(<system::Pallet<T>>::block_number()),
+			PackageAction::Deliver =>
+				package.delivered_on = Some(<system::Pallet<T>>::block_number()),
+			_ => (),
+		}
+
+		Self::record_status(&package.client, &package.id, package.status, actor);
+
+		PackagesByStatus::<T>::remove(previous, (&package.client, &package.id));
+		PackagesByStatus::<T>::insert(package.status, (&package.client, &package.id), ());
+
+		if package.status.is_concluded() {
+			if let Some(carrier) = &package.carrier {
+				CarrierPackages::<T>::mutate(carrier, |packages| {
+					packages.retain(|(client, id)| *client != package.client || *id != package.id)
+				});
+			}
+
+			Self::queue_concluded_package(&package.client, &package.id);
+		}
+
+		Ok(())
+	}
+
+	/// Queue a package for removal once its retention period has passed
+	fn queue_concluded_package(client: &T::AccountId, package_id: &PackageId) {
+		let index = ConcludedTail::<T>::get();
+
+		ConcludedPackages::<T>::insert(
+			index,
+			(client.clone(), *package_id, <system::Pallet<T>>::block_number()),
+		);
+		ConcludedTail::<T>::put(index.saturating_add(1));
+	}
+
+	/// Append a status change to a package's timeline, dropping the oldest entry once full
+	pub(crate) fn record_status(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		status: PackageStatus,
+		actor: &T::AccountId,
+	) {
+		let entry = TimelineEntry {
+			status,
+			block: <system::Pallet<T>>::block_number(),
+			timestamp: T::UnixTime::now().as_millis().saturated_into(),
+			actor: actor.clone(),
+		};
+
+		Timelines::<T>::mutate(&client, &package_id, |timeline| {
+			if let Err(entry) = timeline.try_push(entry) {
+				if !timeline.is_empty() {
+					timeline.remove(0);
+					let _ = timeline.try_push(entry);
+				}
+			}
+		});
+	}
+
+	/// Reserve or release the difference between the deposit held for a package's metadata
+	/// and the deposit its metadata and external reference now require
+	fn update_metadata_deposit(client: &T::AccountId, package_id: &PackageId) -> DispatchResult {
+		let metadata_bytes: usize = PackageMetadata::<T>::get(client, package_id)
+			.iter()
+			.map(|(key, value)| key.len() + value.len())
+			.sum();
+		let reference_bytes =
+			ExternalReferences::<T>::get(client, package_id).map_or(0, |reference| reference.len());
+
+		let required = T::MetadataDepositPerByte::get()
+			.saturating_mul(((metadata_bytes + reference_bytes) as u32).into());
+		let held = MetadataDeposits::<T>::get(client, package_id);
+
+		if required > held {
+			T::Currency::reserve(client, required - held)
+				.map_err(|_| Error::<T>::InsufficientFunds)?;
+		} else {
+			T::Currency::unreserve(client, held - required);
+		}
+
+		if required.is_zero() {
+			MetadataDeposits::<T>::remove(client, package_id);
+		} else {
+			MetadataDeposits::<T>::insert(client, package_id, required);
+		}
+
+		Ok(())
+	}
+
+	/// Packages with the given status as (client, package id),
+	/// skipping the first `offset` and returning at most `limit`
+	pub fn packages_with_status(
+		status: PackageStatus,
+		offset: u32,
+		limit: u32,
+	) -> Vec<(T::AccountId, PackageId)> {
+		PackagesByStatus::<T>::iter_key_prefix(status)
+			.skip(offset as usize)
+			.take(limit as usize)
+			.collect()
+	}
+
+	/// Job board of packages waiting for a carrier, paged like `packages_with_status`
+	pub fn open_packages(offset: u32, limit: u32) -> Vec<(T::AccountId, PackageId)> {
+		Self::packages_with_status(PackageStatus::New, offset, limit)
+	}
+
+	/// Packages assigned to a carrier which have not concluded yet
+	pub fn carrier_workload(carrier: &T::AccountId) -> Vec<(T::AccountId, PackageId)> {
+		CarrierPackages::<T>::get(carrier).into_inner()
+	}
+
+	/// Every recorded status change for a package, oldest first
+	pub fn timeline(
+		client: &T::AccountId,
+		package_id: &PackageId,
+	) -> Vec<TimelineEntry<T::AccountId, BlockNumberFor<T>>> {
+		Timelines::<T>::get(&client, &package_id).into_inner()
+	}
+
+	fn apply_transition(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		action: PackageAction,
+		actor: &T::AccountId,
+	) -> Result<Package<T>, DispatchError> {
+		let mut package =
+			Packages::<T>::get(&client, &package_id).ok_or(Error::<T>::PackageDoesNotExist)?;
+
+		Self::transition(&mut package, action, actor)?;
+		Packages::<T>::insert(&client, &package_id, package.clone());
+
+		Ok(package)
+	}
+}
+
+impl<T: Config> PackageInspect<T> for Pallet<T> {
+	fn package(client: &T::AccountId, package_id: &PackageId) -> Option<Package<T>> {
+		Packages::<T>::get(client, package_id)
+	}
+
+	fn manifest(client: &T::AccountId, package_id: &PackageId) -> Option<Manifest<T>> {
+		Manifests::<T>::get(client, package_id)
+	}
+
+	fn client_of(package_id: &PackageId) -> Option<T::AccountId> {
+		PackageClients::<T>::get(package_id)
+	}
+
+	fn package_by_id(package_id: &PackageId) -> Option<(T::AccountId, Package<T>)> {
+		let client = PackageClients::<T>::get(package_id)?;
+		let package = Packages::<T>::get(&client, package_id)?;
+
+		Some((client, package))
+	}
+
+	fn packages_of(client: &T::AccountId, offset: u32, limit: u32) -> Vec<(PackageId, Package<T>)> {
+		// Skip over keys so that only the packages on the page are decoded
+		Packages::<T>::iter_key_prefix(client)
+			.skip(offset as usize)
+			.take(limit as usize)
+			.filter_map(|package_id| {
+				Packages::<T>::get(client, package_id).map(|package| (package_id, package))
+			})
+			.collect()
+	}
+
+	fn metadata(client: &T::AccountId, package_id: &PackageId) -> Metadata<T> {
+		PackageMetadata::<T>::get(client, package_id)
+	}
+
+	fn package_by_reference(client: &T::AccountId, reference: &[u8]) -> Option<PackageId> {
+		let reference: ExternalReference<T> = reference.to_vec().try_into().ok()?;
+
+		PackagesByReference::<T>::get(client, reference)
+	}
+
+	fn line_items(client: &T::AccountId, package_id: &PackageId) -> LineItems<T> {
+		PackageLineItems::<T>::get(client, package_id)
+	}
+
+	fn missing_value(client: &T::AccountId, package_id: &PackageId) -> Option<BalanceOf<T>> {
+		let received = PackageReceived::<T>::get(client, package_id)?;
+
+		Some(PackageLineItems::<T>::get(client, package_id).iter().zip(received.iter()).fold(
+			Zero::zero(),
+			|missing: BalanceOf<T>, (item, received)| {
+				missing.saturating_add(item.value_of(item.quantity.saturating_sub(*received)))
+			},
+		))
+	}
+
+	fn packages_with_status(
+		status: PackageStatus,
+		offset: u32,
+		limit: u32,
+	) -> Vec<(T::AccountId, PackageId)> {
+		Self::packages_with_status(status, offset, limit)
+	}
+
+	fn carrier_workload(carrier: &T::AccountId) -> Vec<(T::AccountId, PackageId)> {
+		Self::carrier_workload(carrier)
+	}
+
+	fn ensure_can_transition(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		action: PackageAction,
+	) -> Result<Package<T>, DispatchError> {
+		let package =
+			Packages::<T>::get(&client, &package_id).ok_or(Error::<T>::PackageDoesNotExist)?;
+
+		package.status.next(action).ok_or(Self::transition_error(action))?;
+
+		Ok(package)
+	}
+
+	fn ensure_carrier(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Result<Package<T>, DispatchError> {
+		let package =
+			Packages::<T>::get(&client, &package_id).ok_or(Error::<T>::PackageDoesNotExist)?;
+
+		ensure!(package.carrier.as_ref() == Some(carrier), Error::<T>::InvalidCarrier);
+
+		Ok(package)
+	}
+
+	fn next_prunable(now: BlockNumberFor<T>) -> Option<(T::AccountId, PackageId)> {
+		let (client, package_id, concluded_on) =
+			ConcludedPackages::<T>::get(ConcludedHead::<T>::get())?;
+
+		// Packages are queued in the order they conclude, so nothing behind this one is due either
+		if concluded_on.saturating_add(T::ConcludedRetentionPeriod::get()) > now {
+			return None
+		}
+
+		Some((client, package_id))
+	}
+}
+
+impl<T: Config> PackageMutate<T> for Pallet<T> {
+	fn create_package(
+		client: &T::AccountId,
+		package_id: Option<PackageId>,
+		pickup: Vec<u8>,
+		destination: Vec<u8>,
+		manifest: Manifest<T>,
+	) -> Result<PackageId, DispatchError> {
+		let pickup_bounded: BoundedVec<_, _> =
+			pickup.try_into().map_err(|_| Error::<T>::InvalidThreeWordAddress)?;
+		let destination_bounded: BoundedVec<_, _> =
+			destination.try_into().map_err(|_| Error::<T>::InvalidThreeWordAddress)?;
+
+		// IDs chosen by a client are scoped to them, so one client can never claim another's.
+		// Derived IDs are unique across all clients, so their packages can be found by ID alone.
+		let derived = package_id.is_none();
+		let package_id = package_id.unwrap_or_else(|| Self::next_package_id(client));
+		ensure!(
+			!Packages::<T>::contains_key(client, &package_id) &&
+				!(derived && PackageClients::<T>::contains_key(&package_id)),
+			Error::<T>::PackageExists
+		);
+
+		// Reserve ernest deposit
+		let ernest_deposit = T::ErnestDeposit::get();
+		T::Currency::reserve(client, ernest_deposit).map_err(|_| Error::<T>::InsufficientFunds)?;
+		ErnestDeposits::<T>::insert(client, &package_id, ernest_deposit);
+
+		Packages::<T>::insert(
+			client,
+			&package_id,
+			Package::new(package_id, client.clone(), pickup_bounded, destination_bounded),
+		);
+		Manifests::<T>::insert(client, &package_id, manifest);
+		if derived {
+			PackageClients::<T>::insert(&package_id, client);
+		}
+
+		Self::record_status(client, &package_id, PackageStatus::New, client);
+		PackagesByStatus::<T>::insert(PackageStatus::New, (client, &package_id), ());
+
+		Self::deposit_event(Event::<T>::PackageCreated {
+			client: client.clone(),
+			package: package_id,
+		});
+
+		Ok(package_id)
 	}
 
-	pub fn package_is_in_transit(client: &T::AccountId, package_id: &PackageId) -> bool {
-		let package = Self::get_package(&client, &package_id);
-		package.is_some() && package.unwrap().status == PackageStatus::InTransit
+	fn update_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		pickup: Vec<u8>,
+		destination: Vec<u8>,
+		mut manifest: Manifest<T>,
+	) -> DispatchResult {
+		// Details can only change before a carrier has agreed to ship the package
+		let mut package = Self::ensure_can_transition(client, package_id, PackageAction::Assign)?;
+
+		package.pickup = pickup.try_into().map_err(|_| Error::<T>::InvalidThreeWordAddress)?;
+		package.destination =
+			destination.try_into().map_err(|_| Error::<T>::InvalidThreeWordAddress)?;
+
+		// Line items still decide the manifest's totals
+		let line_items = PackageLineItems::<T>::get(client, package_id);
+		if !line_items.is_empty() {
+			Self::apply_line_item_totals(&mut manifest, &line_items);
+		}
+
+		Packages::<T>::insert(client, package_id, package);
+		Manifests::<T>::insert(client, package_id, manifest);
+
+		Self::deposit_event(Event::<T>::PackageUpdated {
+			client: client.clone(),
+			package: *package_id,
+		});
+
+		Ok(())
 	}
 
-	pub fn assign_package(
+	fn assign_package(
 		client: &T::AccountId,
 		package_id: &PackageId,
 		carrier: &T::AccountId,
@@ -69,7 +650,7 @@ impl<T: Config> Pallet<T> {
 			Packages::<T>::get(&client, &package_id).ok_or(Error::<T>::PackageDoesNotExist)?;
 
 		package.carrier = Some(carrier.clone());
-		package.status = PackageStatus::Assigned;
+		Self::transition(&mut package, PackageAction::Assign, client)?;
 		Packages::<T>::insert(&client, &package_id, package);
 
 		Self::deposit_event(Event::<T>::PackageAssigned {
@@ -81,28 +662,71 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	pub fn cancel_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult {
-		let mut package =
-			Packages::<T>::get(&client, &package_id).ok_or(Error::<T>::PackageDoesNotExist)?;
+	fn collect_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult {
+		// Ensure package exists and has been assigned to this carrier
+		Self::ensure_carrier(client, package_id, carrier)?;
 
-		package.status = PackageStatus::Cancelled;
-		Packages::<T>::insert(&client, &package_id, package);
+		// Ensure that package has not already been collected, delivered, or cancelled
+		Self::apply_transition(client, package_id, PackageAction::Collect, carrier)?;
 
-		Self::deposit_event(Event::<T>::PackageCancelled {
+		Self::deposit_event(Event::<T>::PackageCollected {
 			client: client.clone(),
 			package: package_id.clone(),
+			carrier: carrier.clone(),
 		});
 
 		Ok(())
 	}
 
-	pub fn deliver_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult {
-		let mut package =
-			Packages::<T>::get(&client, &package_id).ok_or(Error::<T>::PackageDoesNotExist)?;
+	fn hold_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult {
+		Self::ensure_carrier(client, package_id, carrier)?;
+		Self::apply_transition(client, package_id, PackageAction::Hold, carrier)?;
 
-		package.delivered_on = Some(<system::Pallet<T>>::block_number());
-		package.status = PackageStatus::Delivered;
-		Packages::<T>::insert(&client, &package_id, package);
+		Self::deposit_event(Event::<T>::PackageHeld {
+			client: client.clone(),
+			package: package_id.clone(),
+			carrier: carrier.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn release_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		actor: &T::AccountId,
+	) -> DispatchResult {
+		Self::apply_transition(client, package_id, PackageAction::Release, actor)?;
+
+		Self::deposit_event(Event::<T>::PackageReleased {
+			client: client.clone(),
+			package: package_id.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn dispute_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult {
+		Self::apply_transition(client, package_id, PackageAction::Dispute, client)?;
+
+		Self::deposit_event(Event::<T>::PackageDisputed {
+			client: client.clone(),
+			package: package_id.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn deliver_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult {
+		Self::apply_transition(client, package_id, PackageAction::Deliver, client)?;
 
 		Self::deposit_event(Event::<T>::PackageDelivered {
 			client: client.clone(),
@@ -112,17 +736,403 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	pub fn remove_concluded_packages_and_manifests(
-	) -> Option<BoundedVec<(T::AccountId, PackageId), T::MaxConcludedPackages>> {
-		let concluded_packages = ConcludedPackages::<T>::take();
+	fn return_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult {
+		Self::ensure_carrier(client, package_id, carrier)?;
+		Self::apply_transition(client, package_id, PackageAction::Return, carrier)?;
+
+		Self::deposit_event(Event::<T>::PackageReturned {
+			client: client.clone(),
+			package: package_id.clone(),
+			carrier: carrier.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn report_package_lost(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult {
+		Self::ensure_carrier(client, package_id, carrier)?;
+		Self::apply_transition(client, package_id, PackageAction::ReportLost, carrier)?;
+
+		Self::deposit_event(Event::<T>::PackageLost {
+			client: client.clone(),
+			package: package_id.clone(),
+			carrier: carrier.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn cancel_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult {
+		Self::apply_transition(client, package_id, PackageAction::Cancel, client)?;
+
+		Self::deposit_event(Event::<T>::PackageCancelled {
+			client: client.clone(),
+			package: package_id.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn expire_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		actor: &T::AccountId,
+	) -> DispatchResult {
+		let package = Self::ensure_can_transition(client, package_id, PackageAction::Expire)?;
+
+		// Packages can only expire once they've waited long enough for a carrier
+		ensure!(
+			package.requested_on.saturating_add(T::PackageExpiry::get()) <=
+				<system::Pallet<T>>::block_number(),
+			Error::<T>::PackageNotExpired
+		);
+
+		Self::apply_transition(client, package_id, PackageAction::Expire, actor)?;
+
+		Self::deposit_event(Event::<T>::PackageExpired {
+			client: client.clone(),
+			package: package_id.clone(),
+		});
+
+		Ok(())
+	}
+
+	fn release_ernest_deposit(client: &T::AccountId, package_id: &PackageId) {
+		T::Currency::unreserve(client, ErnestDeposits::<T>::take(client, package_id));
+	}
+
+	fn set_line_items(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		line_items: Vec<LineItem<T>>,
+	) -> DispatchResult {
+		// Contents can only change before a carrier has agreed to ship them
+		Self::ensure_can_transition(client, package_id, PackageAction::Assign)?;
+
+		let line_items: LineItems<T> =
+			line_items.try_into().map_err(|_| Error::<T>::TooManyLineItems)?;
+		let count = line_items.len() as u32;
+
+		// The manifest's totals are those of its line items
+		if !line_items.is_empty() {
+			Manifests::<T>::try_mutate(client, package_id, |manifest| {
+				let manifest = manifest.as_mut().ok_or(Error::<T>::PackageDoesNotExist)?;
+				Self::apply_line_item_totals(manifest, &line_items);
+
+				Ok::<_, Error<T>>(())
+			})?;
+		}
+
+		if line_items.is_empty() {
+			PackageLineItems::<T>::remove(client, package_id);
+		} else {
+			PackageLineItems::<T>::insert(client, package_id, line_items);
+		}
+
+		Self::deposit_event(Event::<T>::LineItemsSet {
+			client: client.clone(),
+			package: *package_id,
+			count,
+		});
+
+		Ok(())
+	}
+
+	fn confirm_received(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		received: Vec<u32>,
+	) -> Result<Perbill, DispatchError> {
+		let line_items = PackageLineItems::<T>::get(client, package_id);
+		ensure!(!line_items.is_empty(), Error::<T>::NoLineItems);
+		ensure!(
+			received.len() == line_items.len() &&
+				line_items
+					.iter()
+					.zip(received.iter())
+					.all(|(item, received)| { *received <= item.quantity }),
+			Error::<T>::InvalidReceivedQuantities
+		);
+
+		let (shipped_value, received_value) = line_items.iter().zip(received.iter()).fold(
+			(BalanceOf::<T>::zero(), BalanceOf::<T>::zero()),
+			|(shipped, delivered), (item, received)| {
+				(
+					shipped.saturating_add(item.value_of(item.quantity)),
+					delivered.saturating_add(item.value_of(*received)),
+				)
+			},
+		);
+		let (shipped_units, received_units) = line_items.iter().zip(received.iter()).fold(
+			(0u32, 0u32),
+			|(shipped, delivered), (item, received)| {
+				(shipped.saturating_add(item.quantity), delivered.saturating_add(*received))
+			},
+		);
+
+		// Items without a declared value are shared out by quantity instead
+		let share = if shipped_value.is_zero() {
+			Perbill::from_rational(received_units, shipped_units)
+		} else {
+			Perbill::from_rational(received_value, shipped_value)
+		};
+
+		PackageReceived::<T>::insert(
+			client,
+			package_id,
+			ReceivedQuantities::<T>::truncate_from(received),
+		);
+
+		Self::deposit_event(Event::<T>::LineItemsReceived {
+			client: client.clone(),
+			package: *package_id,
+			received: share,
+		});
+
+		Ok(share)
+	}
+
+	fn set_metadata(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		key: Vec<u8>,
+		value: Vec<u8>,
+	) -> DispatchResult {
+		ensure!(Packages::<T>::contains_key(client, package_id), Error::<T>::PackageDoesNotExist);
+
+		let key: MetadataKey<T> = key.try_into().map_err(|_| Error::<T>::InvalidMetadata)?;
+		let value: MetadataValue<T> = value.try_into().map_err(|_| Error::<T>::InvalidMetadata)?;
+
+		PackageMetadata::<T>::try_mutate(client, package_id, |metadata| {
+			metadata.try_insert(key.clone(), value).map(|_| ())
+		})
+		.map_err(|_| Error::<T>::TooManyMetadataEntries)?;
+		Self::update_metadata_deposit(client, package_id)?;
+
+		Self::deposit_event(Event::<T>::MetadataSet {
+			client: client.clone(),
+			package: *package_id,
+			key,
+		});
+
+		Ok(())
+	}
+
+	fn clear_metadata(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		key: Vec<u8>,
+	) -> DispatchResult {
+		let key: MetadataKey<T> = key.try_into().map_err(|_| Error::<T>::MetadataDoesNotExist)?;
+
+		PackageMetadata::<T>::try_mutate_exists(client, package_id, |metadata| {
+			let entries = metadata.as_mut().ok_or(Error::<T>::MetadataDoesNotExist)?;
+			entries.remove(&key).ok_or(Error::<T>::MetadataDoesNotExist)?;
+
+			if entries.is_empty() {
+				*metadata = None;
+			}
+
+			Ok::<_, Error<T>>(())
+		})?;
+		Self::update_metadata_deposit(client, package_id)?;
+
+		Self::deposit_event(Event::<T>::MetadataCleared {
+			client: client.clone(),
+			package: *package_id,
+			key,
+		});
+
+		Ok(())
+	}
+
+	fn set_external_reference(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		reference: Option<Vec<u8>>,
+	) -> DispatchResult {
+		ensure!(Packages::<T>::contains_key(client, package_id), Error::<T>::PackageDoesNotExist);
+
+		let reference: Option<ExternalReference<T>> = reference
+			.map(|reference| reference.try_into().map_err(|_| Error::<T>::InvalidReference))
+			.transpose()?;
+
+		if let Some(reference) = &reference {
+			// References must identify a single package among the client's own
+			ensure!(
+				PackagesByReference::<T>::get(client, reference)
+					.map_or(true, |existing| existing == *package_id),
+				Error::<T>::ReferenceInUse
+			);
+		}
+
+		if let Some(previous) = ExternalReferences::<T>::take(client, package_id) {
+			PackagesByReference::<T>::remove(client, previous);
+		}
+		if let Some(reference) = &reference {
+			ExternalReferences::<T>::insert(client, package_id, reference);
+			PackagesByReference::<T>::insert(client, reference, package_id);
+		}
+		Self::update_metadata_deposit(client, package_id)?;
+
+		Self::deposit_event(Event::<T>::ExternalReferenceSet {
+			client: client.clone(),
+			package: *package_id,
+			reference,
+		});
+
+		Ok(())
+	}
+
+	fn prune_next_concluded(now: BlockNumberFor<T>) -> Option<(T::AccountId, PackageId)> {
+		let (client, package_id) = Self::next_prunable(now)?;
+		let head = ConcludedHead::<T>::get();
+
+		if let Some(package) = Packages::<T>::take(&client, &package_id) {
+			PackagesByStatus::<T>::remove(package.status, (&client, &package_id));
+		}
+		Manifests::<T>::remove(&client, &package_id);
+		Timelines::<T>::remove(&client, &package_id);
+		if PackageClients::<T>::get(&package_id).as_ref() == Some(&client) {
+			PackageClients::<T>::remove(&package_id);
+		}
+		PackageMetadata::<T>::remove(&client, &package_id);
+		PackageLineItems::<T>::remove(&client, &package_id);
+		PackageReceived::<T>::remove(&client, &package_id);
+		if let Some(reference) = ExternalReferences::<T>::take(&client, &package_id) {
+			PackagesByReference::<T>::remove(&client, reference);
+		}
+		Self::release_ernest_deposit(&client, &package_id);
+		T::Currency::unreserve(&client, MetadataDeposits::<T>::take(&client, &package_id));
+
+		ConcludedPackages::<T>::remove(head);
+		ConcludedHead::<T>::put(head.saturating_add(1));
+
+		Some((client, package_id))
+	}
+}
+
+#[cfg(any(feature = "try-runtime", test))]
+impl<T: Config> Pallet<T> {
+	/// Check that every package has a manifest and is consistent with the package indexes
+	pub fn do_try_state() -> Result<(), DispatchError> {
+		let mut packages = 0usize;
+		let mut concluded = 0u64;
+
+		for (client, package_id, package) in Packages::<T>::iter() {
+			packages += 1;
+
+			ensure!(
+				package.client == client && package.id == package_id,
+				"Package is stored under another package's key"
+			);
+			ensure!(Manifests::<T>::contains_key(&client, &package_id), "Package has no manifest");
+			ensure!(
+				PackagesByStatus::<T>::contains_key(package.status, (&client, &package_id)),
+				"Package is missing from the status index"
+			);
 
-		if let Some(concluded_packages) = concluded_packages.clone() {
-			for concluded_package in concluded_packages.iter() {
-				Packages::<T>::remove(&concluded_package.0, &concluded_package.1);
-				Manifests::<T>::remove(&concluded_package.0, &concluded_package.1);
+			// Only packages which never left the New status have no carrier
+			let unassigned = matches!(
+				package.status,
+				PackageStatus::New | PackageStatus::Cancelled | PackageStatus::Expired
+			);
+			ensure!(
+				package.carrier.is_none() == unassigned,
+				"Package carrier does not match its status"
+			);
+
+			if package.status.is_concluded() {
+				concluded += 1;
+			} else if let Some(carrier) = &package.carrier {
+				ensure!(
+					CarrierPackages::<T>::get(carrier).contains(&(client, package_id)),
+					"Assigned package is missing from its carrier's workload"
+				);
 			}
 		}
 
-		concluded_packages
+		ensure!(Manifests::<T>::iter_keys().count() == packages, "Manifest has no package");
+		ensure!(
+			PackagesByStatus::<T>::iter_keys().count() == packages,
+			"Status index refers to a missing package"
+		);
+
+		ensure!(
+			ErnestDeposits::<T>::iter_keys()
+				.all(|(client, package_id)| Packages::<T>::contains_key(client, package_id)),
+			"Ernest deposit is held for a missing package"
+		);
+
+		for (client, package_id, reference) in ExternalReferences::<T>::iter() {
+			ensure!(
+				Packages::<T>::contains_key(&client, &package_id),
+				"External reference is held for a missing package"
+			);
+			ensure!(
+				PackagesByReference::<T>::get(&client, &reference) == Some(package_id),
+				"External reference is missing from the reference index"
+			);
+		}
+		ensure!(
+			PackagesByReference::<T>::iter_keys().count() ==
+				ExternalReferences::<T>::iter_keys().count(),
+			"Reference index refers to a package without that reference"
+		);
+		ensure!(
+			PackageMetadata::<T>::iter_keys()
+				.chain(MetadataDeposits::<T>::iter_keys())
+				.all(|(client, package_id)| Packages::<T>::contains_key(client, package_id)),
+			"Metadata is held for a missing package"
+		);
+		ensure!(
+			PackageLineItems::<T>::iter_keys()
+				.all(|(client, package_id)| Packages::<T>::contains_key(client, package_id)),
+			"Line items are held for a missing package"
+		);
+		for (client, package_id, received) in PackageReceived::<T>::iter() {
+			ensure!(
+				received.len() ==
+					PackageLineItems::<T>::decode_len(&client, &package_id).unwrap_or(0),
+				"Received quantities do not match the package's line items"
+			);
+		}
+
+		for (package_id, client) in PackageClients::<T>::iter() {
+			ensure!(
+				Packages::<T>::contains_key(&client, &package_id),
+				"Package index refers to a missing package"
+			);
+		}
+
+		for (carrier, workload) in CarrierPackages::<T>::iter() {
+			for (client, package_id) in workload {
+				let package = Packages::<T>::get(&client, &package_id)
+					.ok_or("Carrier workload refers to a missing package")?;
+				ensure!(
+					package.carrier == Some(carrier.clone()) && !package.status.is_concluded(),
+					"Carrier workload refers to a package the carrier is not working on"
+				);
+			}
+		}
+
+		// Every concluded package is queued for removal exactly once
+		let (head, tail) = (ConcludedHead::<T>::get(), ConcludedTail::<T>::get());
+		ensure!(head <= tail, "Concluded queue head is past its tail");
+		ensure!(tail - head == concluded, "Concluded packages do not match the removal queue");
+		ensure!(
+			ConcludedPackages::<T>::iter_keys().all(|index| (head..tail).contains(&index)),
+			"Concluded queue has an entry outside of its bounds"
+		);
+
+		Ok(())
 	}
 }
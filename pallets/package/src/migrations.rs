@@ -0,0 +1,308 @@
+//! Storage migrations for the package pallet.
+//!
+//! Each migration checks the on-chain storage version before touching any state,
+//! so it is safe to leave in the runtime's `Migrations` after it has run.
+
+use super::*;
+use codec::{Decode, Encode};
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::TryRuntimeError;
+use frame_support::{
+	sp_runtime::traits::Zero,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::marker::PhantomData;
+
+/// Version 1 adds the client's declared value to every manifest
+pub mod v1 {
+	use super::*;
+
+	/// Manifest as stored before version 1
+	#[derive(Encode, Decode)]
+	pub struct OldManifest<T: Config> {
+		pub description: BoundedVec<u8, T::DescriptionMaxLength>,
+		pub dimensions: Dimensions,
+		pub weight: u32,
+		pub hazardous_materials: bool,
+		pub climate_controlled: bool,
+	}
+
+	/// Existing manifests are given a declared value of zero, as it was never recorded
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut translated = 0u64;
+			Manifests::<T>::translate::<OldManifest<T>, _>(|_, _, old| {
+				translated += 1;
+				Some(Manifest {
+					description: old.description,
+					dimensions: old.dimensions,
+					weight: old.weight,
+					hazardous_materials: old.hazardous_materials,
+					climate_controlled: old.climate_controlled,
+					declared_value: Zero::zero(),
+				})
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok((Manifests::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let manifests =
+				u32::decode(&mut &state[..]).map_err(|_| "Pre-upgrade state is invalid")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"Storage version was not updated"
+			);
+			// Values which fail to decode are skipped, so any corrupt manifest changes the count
+			ensure!(
+				Manifests::<T>::iter_values().count() as u32 == manifests,
+				"Manifests do not decode after the migration"
+			);
+
+			Ok(())
+		}
+	}
+}
+
+/// Version 2 records the ernest deposit held for every package which has not concluded
+pub mod v2 {
+	use super::*;
+
+	/// Deposits of open packages were reserved at the then constant `ErnestDeposit`,
+	/// which is still its value when this migration runs
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let ernest_deposit = T::ErnestDeposit::get();
+			let mut packages = 0u64;
+			let mut recorded = 0u64;
+
+			for (client, package_id, package) in Packages::<T>::iter() {
+				packages += 1;
+				if !package.status.is_concluded() {
+					ErnestDeposits::<T>::insert(client, package_id, ernest_deposit);
+					recorded += 1;
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(packages + 2, recorded + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			let open = Packages::<T>::iter_values()
+				.filter(|package| !package.status.is_concluded())
+				.count() as u32;
+
+			Ok(open.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let open = u32::decode(&mut &state[..]).map_err(|_| "Pre-upgrade state is invalid")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 2,
+				"Storage version was not updated"
+			);
+			ensure!(
+				ErnestDeposits::<T>::iter_keys().count() as u32 == open,
+				"Ernest deposit was not recorded for every open package"
+			);
+
+			Ok(())
+		}
+	}
+}
+
+/// Version 3 adds the index of packages with a derived ID
+pub mod v3 {
+	use super::*;
+
+	/// Every package created before version 3 has an ID chosen by its client. Those IDs are
+	/// only unique per client and are never indexed, so only the storage version changes.
+	pub struct MigrateToV3<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			StorageVersion::new(3).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 3,
+				"Storage version was not updated"
+			);
+			Ok(())
+		}
+	}
+}
+
+/// Version 4 rebuilds the package indexes, which were introduced without backfilling the
+/// packages created before them
+pub mod v4 {
+	use super::*;
+	use sp_std::{collections::btree_map::BTreeMap, vec};
+
+	/// `PackagesByStatus`, `CarrierPackages` and the queue of concluded packages are rebuilt from
+	/// `Packages`. Packages which were already queued keep the block they concluded in, others
+	/// are queued at the last block recorded on them. Packages without a timeline are given one
+	/// from the blocks recorded on them, with no timestamps as those were never kept.
+	pub struct MigrateToV4<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() != 3 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let queued: BTreeMap<_, _> = ConcludedPackages::<T>::drain()
+				.map(|(_, (client, package_id, concluded_on))| ((client, package_id), concluded_on))
+				.collect();
+			let _ = PackagesByStatus::<T>::clear(u32::MAX, None);
+			let _ = CarrierPackages::<T>::clear(u32::MAX, None);
+
+			let mut packages = 0u64;
+			let mut timelines = 0u64;
+			let mut concluded = Vec::new();
+			let mut workloads = BTreeMap::<T::AccountId, Vec<(T::AccountId, PackageId)>>::new();
+
+			for (client, package_id, package) in Packages::<T>::iter() {
+				packages += 1;
+				PackagesByStatus::<T>::insert(package.status, (&client, &package_id), ());
+
+				if !Timelines::<T>::contains_key(&client, &package_id) {
+					Timelines::<T>::insert(&client, &package_id, timeline_of(&package));
+					timelines += 1;
+				}
+
+				if package.status.is_concluded() {
+					let concluded_on = queued
+						.get(&(client.clone(), package_id))
+						.copied()
+						.unwrap_or_else(|| last_recorded_block(&package));
+					concluded.push((client, package_id, concluded_on));
+				} else if let Some(carrier) = package.carrier {
+					workloads.entry(carrier).or_default().push((client, package_id));
+				}
+			}
+
+			// Packages are pruned in queue order, which must follow the block they concluded in
+			concluded.sort_by_key(|(_, _, concluded_on)| *concluded_on);
+			let tail = concluded.len() as u64;
+			for (index, entry) in concluded.into_iter().enumerate() {
+				ConcludedPackages::<T>::insert(index as u64, entry);
+			}
+			ConcludedHead::<T>::put(0);
+			ConcludedTail::<T>::put(tail);
+
+			let carriers = workloads.len() as u64;
+			for (carrier, workload) in workloads {
+				// A workload only exceeds its bound if the bound was lowered after its packages
+				// were assigned, in which case the packages past the bound are left out
+				CarrierPackages::<T>::insert(carrier, BoundedVec::truncate_from(workload));
+			}
+
+			StorageVersion::new(4).put::<Pallet<T>>();
+
+			let queued = queued.len() as u64;
+			T::DbWeight::get().reads_writes(
+				queued + packages * 2 + 1,
+				queued + packages + timelines + tail + carriers + 3,
+			)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			let concluded = Packages::<T>::iter_values()
+				.filter(|package| package.status.is_concluded())
+				.count() as u64;
+
+			Ok((Packages::<T>::iter_keys().count() as u32, concluded).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let (packages, concluded) = <(u32, u64)>::decode(&mut &state[..])
+				.map_err(|_| "Pre-upgrade state is invalid")?;
+
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 4,
+				"Storage version was not updated"
+			);
+			ensure!(
+				PackagesByStatus::<T>::iter_keys().count() as u32 == packages,
+				"Status index does not hold every package"
+			);
+			ensure!(
+				ConcludedTail::<T>::get() - ConcludedHead::<T>::get() == concluded,
+				"Concluded packages were not all queued for removal"
+			);
+			ensure!(
+				Packages::<T>::iter_keys().all(|(client, package_id)| {
+					Timelines::<T>::contains_key(client, package_id)
+				}),
+				"Package has no timeline"
+			);
+
+			Pallet::<T>::do_try_state().map_err(Into::into)
+		}
+	}
+
+	/// Status changes which can be recovered from the blocks recorded on a package
+	fn timeline_of<T: Config>(
+		package: &Package<T>,
+	) -> BoundedVec<TimelineEntry<T::AccountId, BlockNumberFor<T>>, T::MaxTimelineEntries> {
+		let carrier = package.carrier.clone().unwrap_or_else(|| package.client.clone());
+		let entry = |status, block, actor: &T::AccountId| TimelineEntry {
+			status,
+			block,
+			timestamp: 0,
+			actor: actor.clone(),
+		};
+
+		let mut timeline = vec![entry(PackageStatus::New, package.requested_on, &package.client)];
+		if let Some(collected_on) = package.collected_on {
+			timeline.push(entry(PackageStatus::InTransit, collected_on, &carrier));
+		}
+		if let Some(delivered_on) = package.delivered_on {
+			timeline.push(entry(PackageStatus::Delivered, delivered_on, &package.client));
+		}
+
+		BoundedVec::truncate_from(timeline)
+	}
+
+	/// Latest block recorded on a package
+	fn last_recorded_block<T: Config>(package: &Package<T>) -> BlockNumberFor<T> {
+		package.delivered_on.or(package.collected_on).unwrap_or(package.requested_on)
+	}
+}
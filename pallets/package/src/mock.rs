@@ -0,0 +1,110 @@
+use crate as pallet_package;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Timestamp: pallet_timestamp,
+		Balances: pallet_balances,
+		PackageModule: pallet_package,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = ConstU64<5>;
+	type WeightInfo = ();
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ConstU64<1>;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type FreezeIdentifier = ();
+	type MaxFreezes = ();
+	type RuntimeHoldReason = ();
+	type MaxHolds = ();
+}
+
+impl pallet_package::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ErnestDeposit = ConstU64<10>;
+	type Slashed = ();
+	type DescriptionMaxLength = ConstU32<64>;
+	type MaxConcludedPackages = ConstU32<16>;
+	type ConcludedRetentionPeriod = ConstU64<10>;
+	type PackageExpiry = ConstU64<100>;
+	type UnixTime = Timestamp;
+	type MaxTimelineEntries = ConstU32<4>;
+	type MaxCarrierPackages = ConstU32<2>;
+	type MaxMetadataEntries = ConstU32<2>;
+	type MaxMetadataKeyLength = ConstU32<8>;
+	type MaxMetadataValueLength = ConstU32<16>;
+	type MaxReferenceLength = ConstU32<16>;
+	type MetadataDepositPerByte = ConstU64<1>;
+	type MaxLineItems = ConstU32<3>;
+	type MaxSkuLength = ConstU32<8>;
+}
+
+pub const CLIENT: u64 = 1;
+pub const CARRIER: u64 = 2;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances: vec![(CLIENT, 1_000), (CARRIER, 1_000)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	t.into()
+}
+
+/// Run a test and check the pallet's invariants once it has finished
+pub fn build_and_execute(test: impl FnOnce()) {
+	new_test_ext().execute_with(|| {
+		test();
+		PackageModule::do_try_state().expect("state is consistent");
+	});
+}
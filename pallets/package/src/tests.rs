@@ -0,0 +1,1237 @@
+use crate::{
+	migrations::{v1, v2, v3, v4},
+	mock::*,
+	traits::*,
+	types::*,
+	CarrierPackages, ConcludedHead, ConcludedPackages, ConcludedTail, ErnestDeposits, Error,
+	ExternalReferences, Manifests, MetadataDeposits, PackageClients, PackageLineItems,
+	PackageMetadata, PackageNonce, PackageReceived, Packages, PackagesByReference,
+	PackagesByStatus, Pallet, Timelines,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::DispatchResult,
+	storage::unhashed,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_runtime::{BuildStorage, Perbill};
+
+const PACKAGE_ID: PackageId = [1u8; 32];
+
+fn package_with_status(status: PackageStatus) -> Package<Test> {
+	let mut package = Package::new(
+		PACKAGE_ID,
+		CLIENT,
+		b"filled.count.soap".to_vec().try_into().unwrap(),
+		b"index.home.raft".to_vec().try_into().unwrap(),
+	);
+	package.carrier = Some(CARRIER);
+	package.status = status;
+	package
+}
+
+fn manifest(
+	description: Vec<u8>,
+	hazardous_materials: bool,
+) -> Result<Manifest<Test>, Error<Test>> {
+	Manifest::new(
+		description,
+		Dimensions { length: 10, width: 20, height: 30 },
+		5,
+		hazardous_materials,
+		false,
+		500,
+	)
+}
+
+fn create_package(package_id: PackageId) {
+	assert_ok!(PackageModule::create_package(
+		&CLIENT,
+		Some(package_id),
+		b"filled.count.soap".to_vec(),
+		b"index.home.raft".to_vec(),
+		manifest(b"Books".to_vec(), false).unwrap(),
+	));
+}
+
+fn create_package_as(client: u64, pickup: Vec<u8>, description: Vec<u8>) -> DispatchResult {
+	PackageModule::create_package(
+		&client,
+		Some(PACKAGE_ID),
+		pickup,
+		b"index.home.raft".to_vec(),
+		manifest(description, true)?,
+	)
+	.map(|_| ())
+}
+
+fn line_item(sku: &[u8], quantity: u32, unit_declared_value: u64) -> LineItem<Test> {
+	LineItem::new((sku.to_vec(), b"Item".to_vec(), quantity, 2, unit_declared_value)).unwrap()
+}
+
+/// Expected outcome of every action from every status, in the order of `PackageAction::ALL`.
+/// Written out in full rather than derived from `PACKAGE_TRANSITIONS` or `transition_error`.
+fn transition_table() -> Vec<(PackageStatus, [Result<PackageStatus, Error<Test>>; 10])> {
+	use PackageStatus::*;
+	type E = Error<Test>;
+
+	vec![
+		(
+			New,
+			[
+				Ok(Assigned),                        // Assign
+				Err(E::PackageCannotBeCollected),    // Collect
+				Err(E::PackageNotInTransit),         // Hold
+				Err(E::PackageCannotBeReleased),     // Release
+				Err(E::PackageCannotBeDisputed),     // Dispute
+				Err(E::PackageCannotBeDelivered),    // Deliver
+				Err(E::PackageCannotBeReturned),     // Return
+				Err(E::PackageCannotBeReportedLost), // ReportLost
+				Ok(Cancelled),                       // Cancel
+				Ok(Expired),                         // Expire
+			],
+		),
+		(
+			Assigned,
+			[
+				Err(E::PackageNotNew),               // Assign
+				Ok(InTransit),                       // Collect
+				Err(E::PackageNotInTransit),         // Hold
+				Err(E::PackageCannotBeReleased),     // Release
+				Err(E::PackageCannotBeDisputed),     // Dispute
+				Err(E::PackageCannotBeDelivered),    // Deliver
+				Err(E::PackageCannotBeReturned),     // Return
+				Err(E::PackageCannotBeReportedLost), // ReportLost
+				Err(E::PackageNotNew),               // Cancel
+				Err(E::PackageNotNew),               // Expire
+			],
+		),
+		(
+			InTransit,
+			[
+				Err(E::PackageNotNew),            // Assign
+				Err(E::PackageCannotBeCollected), // Collect
+				Ok(OnHold),                       // Hold
+				Err(E::PackageCannotBeReleased),  // Release
+				Ok(Disputed),                     // Dispute
+				Ok(Delivered),                    // Deliver
+				Ok(Returned),                     // Return
+				Ok(Lost),                         // ReportLost
+				Err(E::PackageNotNew),            // Cancel
+				Err(E::PackageNotNew),            // Expire
+			],
+		),
+		(
+			Delivered,
+			[
+				Err(E::PackageNotNew),               // Assign
+				Err(E::PackageCannotBeCollected),    // Collect
+				Err(E::PackageNotInTransit),         // Hold
+				Err(E::PackageCannotBeReleased),     // Release
+				Err(E::PackageCannotBeDisputed),     // Dispute
+				Err(E::PackageCannotBeDelivered),    // Deliver
+				Err(E::PackageCannotBeReturned),     // Return
+				Err(E::PackageCannotBeReportedLost), // ReportLost
+				Err(E::PackageNotNew),               // Cancel
+				Err(E::PackageNotNew),               // Expire
+			],
+		),
+		(
+			Cancelled,
+			[
+				Err(E::PackageNotNew),               // Assign
+				Err(E::PackageCannotBeCollected),    // Collect
+				Err(E::PackageNotInTransit),         // Hold
+				Err(E::PackageCannotBeReleased),     // Release
+				Err(E::PackageCannotBeDisputed),     // Dispute
+				Err(E::PackageCannotBeDelivered),    // Deliver
+				Err(E::PackageCannotBeReturned),     // Return
+				Err(E::PackageCannotBeReportedLost), // ReportLost
+				Err(E::PackageNotNew),               // Cancel
+				Err(E::PackageNotNew),               // Expire
+			],
+		),
+		(
+			OnHold,
+			[
+				Err(E::PackageNotNew),            // Assign
+				Err(E::PackageCannotBeCollected), // Collect
+				Err(E::PackageNotInTransit),      // Hold
+				Ok(InTransit),                    // Release
+				Ok(Disputed),                     // Dispute
+				Err(E::PackageCannotBeDelivered), // Deliver
+				Ok(Returned),                     // Return
+				Ok(Lost),                         // ReportLost
+				Err(E::PackageNotNew),            // Cancel
+				Err(E::PackageNotNew),            // Expire
+			],
+		),
+		(
+			Disputed,
+			[
+				Err(E::PackageNotNew),            // Assign
+				Err(E::PackageCannotBeCollected), // Collect
+				Err(E::PackageNotInTransit),      // Hold
+				Ok(InTransit),                    // Release
+				Err(E::PackageCannotBeDisputed),  // Dispute
+				Ok(Delivered),                    // Deliver
+				Ok(Returned),                     // Return
+				Ok(Lost),                         // ReportLost
+				Err(E::PackageNotNew),            // Cancel
+				Err(E::PackageNotNew),            // Expire
+			],
+		),
+		(
+			Returned,
+			[
+				Err(E::PackageNotNew),               // Assign
+				Err(E::PackageCannotBeCollected),    // Collect
+				Err(E::PackageNotInTransit),         // Hold
+				Err(E::PackageCannotBeReleased),     // Release
+				Err(E::PackageCannotBeDisputed),     // Dispute
+				Err(E::PackageCannotBeDelivered),    // Deliver
+				Err(E::PackageCannotBeReturned),     // Return
+				Err(E::PackageCannotBeReportedLost), // ReportLost
+				Err(E::PackageNotNew),               // Cancel
+				Err(E::PackageNotNew),               // Expire
+			],
+		),
+		(
+			Lost,
+			[
+				Err(E::PackageNotNew),               // Assign
+				Err(E::PackageCannotBeCollected),    // Collect
+				Err(E::PackageNotInTransit),         // Hold
+				Err(E::PackageCannotBeReleased),     // Release
+				Err(E::PackageCannotBeDisputed),     // Dispute
+				Err(E::PackageCannotBeDelivered),    // Deliver
+				Err(E::PackageCannotBeReturned),     // Return
+				Err(E::PackageCannotBeReportedLost), // ReportLost
+				Err(E::PackageNotNew),               // Cancel
+				Err(E::PackageNotNew),               // Expire
+			],
+		),
+		(
+			Expired,
+			[
+				Err(E::PackageNotNew),               // Assign
+				Err(E::PackageCannotBeCollected),    // Collect
+				Err(E::PackageNotInTransit),         // Hold
+				Err(E::PackageCannotBeReleased),     // Release
+				Err(E::PackageCannotBeDisputed),     // Dispute
+				Err(E::PackageCannotBeDelivered),    // Deliver
+				Err(E::PackageCannotBeReturned),     // Return
+				Err(E::PackageCannotBeReportedLost), // ReportLost
+				Err(E::PackageNotNew),               // Cancel
+				Err(E::PackageNotNew),               // Expire
+			],
+		),
+	]
+}
+
+#[test]
+fn legal_transitions_reach_expected_status() {
+	new_test_ext().execute_with(|| {
+		for (from, action, to) in PACKAGE_TRANSITIONS {
+			let mut package = package_with_status(*from);
+			assert_ok!(Pallet::<Test>::transition(&mut package, *action, &CLIENT));
+			assert_eq!(package.status, *to, "{:?} --{:?}-->", from, action);
+		}
+	});
+}
+
+#[test]
+fn every_status_and_action_has_expected_outcome() {
+	new_test_ext().execute_with(|| {
+		let table = transition_table();
+		assert_eq!(table.len(), PackageStatus::ALL.len());
+
+		for (from, outcomes) in table {
+			for (action, outcome) in PackageAction::ALL.into_iter().zip(outcomes) {
+				let mut package = package_with_status(from);
+				let result = Pallet::<Test>::transition(&mut package, action, &CLIENT);
+
+				match outcome {
+					Ok(to) => {
+						assert_ok!(result);
+						assert_eq!(package.status, to, "{:?} --{:?}-->", from, action);
+					},
+					Err(error) => {
+						assert_eq!(
+							result,
+							Err(error.into()),
+							"{:?} --{:?}--> should be rejected",
+							from,
+							action
+						);
+						assert_eq!(package.status, from);
+					},
+				}
+			}
+		}
+	});
+}
+
+#[test]
+fn concluded_statuses_permit_no_actions() {
+	let concluded = [
+		PackageStatus::Delivered,
+		PackageStatus::Cancelled,
+		PackageStatus::Returned,
+		PackageStatus::Lost,
+		PackageStatus::Expired,
+	];
+
+	for status in PackageStatus::ALL {
+		assert_eq!(status.is_concluded(), concluded.contains(&status), "{:?}", status);
+	}
+}
+
+#[test]
+fn transition_records_collection_and_delivery_blocks() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		let mut package = package_with_status(PackageStatus::Assigned);
+		assert_ok!(Pallet::<Test>::transition(&mut package, PackageAction::Collect, &CARRIER));
+		assert_eq!(package.collected_on, Some(5));
+
+		System::set_block_number(9);
+		assert_ok!(Pallet::<Test>::transition(&mut package, PackageAction::Deliver, &CLIENT));
+		assert_eq!(package.delivered_on, Some(9));
+	});
+}
+
+#[test]
+fn collect_package_uses_state_machine() {
+	new_test_ext().execute_with(|| {
+		Packages::<Test>::insert(CLIENT, PACKAGE_ID, package_with_status(PackageStatus::OnHold));
+
+		assert_noop!(
+			PackageModule::collect_package(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::PackageCannotBeCollected
+		);
+
+		Packages::<Test>::insert(CLIENT, PACKAGE_ID, package_with_status(PackageStatus::Assigned));
+
+		assert_noop!(
+			PackageModule::collect_package(&CLIENT, &PACKAGE_ID, &3),
+			Error::<Test>::InvalidCarrier
+		);
+		assert_ok!(PackageModule::collect_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_eq!(
+			PackageModule::get_package(CLIENT, PACKAGE_ID).unwrap().status,
+			PackageStatus::InTransit
+		);
+	});
+}
+
+#[test]
+fn packages_expire_only_after_expiry_period() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Packages::<Test>::insert(CLIENT, PACKAGE_ID, package_with_status(PackageStatus::New));
+
+		System::set_block_number(100);
+		assert_noop!(
+			Pallet::<Test>::expire_package(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::PackageNotExpired
+		);
+
+		System::set_block_number(101);
+		assert_ok!(Pallet::<Test>::expire_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_eq!(
+			PackageModule::get_package(CLIENT, PACKAGE_ID).unwrap().status,
+			PackageStatus::Expired
+		);
+	});
+}
+
+#[test]
+fn status_changes_are_recorded_in_timeline() {
+	build_and_execute(|| {
+		System::set_block_number(3);
+		Timestamp::set_timestamp(30_000);
+
+		create_package(PACKAGE_ID);
+		assert_ok!(Pallet::<Test>::assign_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+
+		System::set_block_number(4);
+		Timestamp::set_timestamp(36_000);
+		assert_ok!(PackageModule::collect_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+
+		assert_eq!(
+			PackageModule::timeline(&CLIENT, &PACKAGE_ID),
+			vec![
+				TimelineEntry {
+					status: PackageStatus::New,
+					block: 3,
+					timestamp: 30_000,
+					actor: CLIENT
+				},
+				TimelineEntry {
+					status: PackageStatus::Assigned,
+					block: 3,
+					timestamp: 30_000,
+					actor: CLIENT
+				},
+				TimelineEntry {
+					status: PackageStatus::InTransit,
+					block: 4,
+					timestamp: 36_000,
+					actor: CARRIER
+				},
+			]
+		);
+	});
+}
+
+#[test]
+fn timeline_keeps_most_recent_entries_when_full() {
+	new_test_ext().execute_with(|| {
+		let mut package = package_with_status(PackageStatus::InTransit);
+
+		// Hold and release repeatedly to overflow the timeline
+		for block in 1..=6 {
+			System::set_block_number(block);
+			let action = if block % 2 == 1 { PackageAction::Hold } else { PackageAction::Release };
+			assert_ok!(Pallet::<Test>::transition(&mut package, action, &CARRIER));
+		}
+
+		let timeline = Timelines::<Test>::get(CLIENT, PACKAGE_ID);
+		assert_eq!(timeline.len(), 4);
+		assert_eq!(timeline.iter().map(|entry| entry.block).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+	});
+}
+
+#[test]
+fn concluded_packages_are_queued_for_removal() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(7);
+		let mut package = package_with_status(PackageStatus::InTransit);
+
+		assert_ok!(Pallet::<Test>::transition(&mut package, PackageAction::Hold, &CARRIER));
+		assert_eq!(ConcludedTail::<Test>::get(), 0);
+
+		assert_ok!(Pallet::<Test>::transition(&mut package, PackageAction::ReportLost, &CARRIER));
+		assert_eq!(ConcludedTail::<Test>::get(), 1);
+		assert_eq!(ConcludedPackages::<Test>::get(0), Some((CLIENT, PACKAGE_ID, 7)));
+	});
+}
+
+#[test]
+fn concluded_packages_are_kept_for_retention_period() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Packages::<Test>::insert(CLIENT, PACKAGE_ID, package_with_status(PackageStatus::New));
+		assert_ok!(Pallet::<Test>::cancel_package(&CLIENT, &PACKAGE_ID));
+
+		assert_eq!(Pallet::<Test>::prune_next_concluded(10), None);
+		assert!(Packages::<Test>::contains_key(CLIENT, PACKAGE_ID));
+
+		assert_eq!(Pallet::<Test>::prune_next_concluded(11), Some((CLIENT, PACKAGE_ID)));
+		assert!(!Packages::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert!(!Manifests::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert!(!Timelines::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert_eq!(ConcludedHead::<Test>::get(), 1);
+
+		// Queue is now empty
+		assert_eq!(Pallet::<Test>::prune_next_concluded(100), None);
+	});
+}
+
+#[test]
+fn status_and_carrier_indexes_follow_transitions() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		create_package(PACKAGE_ID);
+		assert_eq!(Pallet::<Test>::open_packages(0, 10), vec![(CLIENT, PACKAGE_ID)]);
+
+		assert_ok!(Pallet::<Test>::assign_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert!(Pallet::<Test>::open_packages(0, 10).is_empty());
+		assert_eq!(
+			Pallet::<Test>::packages_with_status(PackageStatus::Assigned, 0, 10),
+			vec![(CLIENT, PACKAGE_ID)]
+		);
+		assert_eq!(Pallet::<Test>::carrier_workload(&CARRIER), vec![(CLIENT, PACKAGE_ID)]);
+
+		assert_ok!(PackageModule::collect_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert!(Pallet::<Test>::packages_with_status(PackageStatus::Assigned, 0, 10).is_empty());
+		assert_eq!(
+			Pallet::<Test>::packages_with_status(PackageStatus::InTransit, 0, 10),
+			vec![(CLIENT, PACKAGE_ID)]
+		);
+
+		// Concluded packages no longer count towards the carrier's workload
+		assert_ok!(Pallet::<Test>::deliver_package(&CLIENT, &PACKAGE_ID));
+		assert!(Pallet::<Test>::carrier_workload(&CARRIER).is_empty());
+		assert_eq!(
+			Pallet::<Test>::packages_with_status(PackageStatus::Delivered, 0, 10),
+			vec![(CLIENT, PACKAGE_ID)]
+		);
+
+		// Pruning removes the package from the status index
+		assert_eq!(Pallet::<Test>::prune_next_concluded(11), Some((CLIENT, PACKAGE_ID)));
+		assert_eq!(PackagesByStatus::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn packages_of_is_paged() {
+	build_and_execute(|| {
+		for id in 1..=3 {
+			create_package([id; 32]);
+		}
+
+		let first = PackageModule::packages_of(&CLIENT, 0, 2);
+		let rest = PackageModule::packages_of(&CLIENT, 2, 2);
+		assert_eq!(first.len(), 2);
+		assert_eq!(rest.len(), 1);
+
+		let mut ids: Vec<_> = first.into_iter().chain(rest).map(|(id, _)| id).collect();
+		ids.sort();
+		assert_eq!(ids, vec![[1; 32], [2; 32], [3; 32]]);
+		assert!(PackageModule::packages_of(&CLIENT, 3, 2).is_empty());
+	});
+}
+
+#[test]
+fn open_packages_are_paged() {
+	build_and_execute(|| {
+		for id in 1..=3 {
+			create_package([id; 32]);
+		}
+
+		let first = Pallet::<Test>::open_packages(0, 2);
+		let rest = Pallet::<Test>::open_packages(2, 2);
+		assert_eq!(first.len(), 2);
+		assert_eq!(rest.len(), 1);
+		assert!(!first.contains(&rest[0]));
+		assert!(Pallet::<Test>::open_packages(3, 2).is_empty());
+	});
+}
+
+#[test]
+fn carrier_workload_is_bounded() {
+	build_and_execute(|| {
+		for id in 1..=3 {
+			create_package([id; 32]);
+		}
+
+		assert_ok!(Pallet::<Test>::assign_package(&CLIENT, &[1; 32], &CARRIER));
+		assert_ok!(Pallet::<Test>::assign_package(&CLIENT, &[2; 32], &CARRIER));
+		assert_noop!(
+			Pallet::<Test>::assign_package(&CLIENT, &[3; 32], &CARRIER),
+			Error::<Test>::CarrierWorkloadFull
+		);
+
+		// Finishing a job frees up room for another
+		assert_ok!(PackageModule::collect_package(&CLIENT, &[1; 32], &CARRIER));
+		assert_ok!(Pallet::<Test>::deliver_package(&CLIENT, &[1; 32]));
+		assert_ok!(Pallet::<Test>::assign_package(&CLIENT, &[3; 32], &CARRIER));
+		assert_eq!(CarrierPackages::<Test>::get(CARRIER).len(), 2);
+	});
+}
+
+#[test]
+fn create_package_reserves_ernest_deposit() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		assert_ok!(create_package_as(CLIENT, b"filled.count.soap".to_vec(), b"Books".to_vec()));
+
+		assert_eq!(Balances::reserved_balance(CLIENT), 10);
+		assert_eq!(Balances::free_balance(CLIENT), 990);
+		assert_eq!(ErnestDeposits::<Test>::get(CLIENT, PACKAGE_ID), 10);
+
+		let package = PackageModule::get_package(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(package.status, PackageStatus::New);
+		assert_eq!(package.carrier, None);
+		assert_eq!(package.requested_on, 1);
+
+		let manifest = Manifests::<Test>::get(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(manifest.description.to_vec(), b"Books".to_vec());
+		assert_eq!(manifest.dimensions, Dimensions { length: 10, width: 20, height: 30 });
+		assert!(manifest.hazardous_materials);
+		assert!(!manifest.climate_controlled);
+		assert_eq!(manifest.declared_value, 500);
+
+		System::assert_last_event(
+			crate::Event::PackageCreated { client: CLIENT, package: PACKAGE_ID }.into(),
+		);
+	});
+}
+
+#[test]
+fn create_package_rejects_duplicate_ids() {
+	build_and_execute(|| {
+		assert_ok!(create_package_as(CLIENT, b"filled.count.soap".to_vec(), b"Books".to_vec()));
+
+		assert_noop!(
+			create_package_as(CLIENT, b"filled.count.soap".to_vec(), b"Books".to_vec()),
+			Error::<Test>::PackageExists
+		);
+
+		// IDs chosen by clients are scoped to them
+		assert_ok!(create_package_as(CARRIER, b"filled.count.soap".to_vec(), b"Books".to_vec()));
+	});
+}
+
+#[test]
+fn packages_with_derived_ids_can_be_found_by_id_alone() {
+	build_and_execute(|| {
+		let package_id = PackageModule::create_package(
+			&CLIENT,
+			None,
+			b"filled.count.soap".to_vec(),
+			b"index.home.raft".to_vec(),
+			manifest(b"Books".to_vec(), false).unwrap(),
+		)
+		.unwrap();
+
+		assert_eq!(PackageModule::client_of(&package_id), Some(CLIENT));
+		let (client, package) = PackageModule::package_by_id(&package_id).unwrap();
+		assert_eq!(client, CLIENT);
+		assert_eq!(package.id, package_id);
+
+		// IDs chosen by clients are not indexed
+		create_package(PACKAGE_ID);
+		assert_eq!(PackageModule::package_by_id(&PACKAGE_ID), None);
+
+		// Pruned packages are removed from the index
+		assert_ok!(Pallet::<Test>::cancel_package(&CLIENT, &package_id));
+		assert_eq!(Pallet::<Test>::prune_next_concluded(11), Some((CLIENT, package_id)));
+		assert_eq!(PackageModule::client_of(&package_id), None);
+	});
+}
+
+#[test]
+fn derived_package_ids_are_distinct() {
+	build_and_execute(|| {
+		let first = PackageModule::next_package_id(&CLIENT);
+		let second = PackageModule::next_package_id(&CLIENT);
+		let other = PackageModule::next_package_id(&CARRIER);
+
+		assert_ne!(first, second);
+		assert_ne!(first, other);
+		assert_ne!(second, other);
+		assert_eq!(PackageNonce::<Test>::get(), 3);
+
+		// The same nonce in a later block gives a different ID
+		PackageNonce::<Test>::put(0);
+		System::set_block_number(2);
+		assert_ne!(PackageModule::next_package_id(&CLIENT), first);
+	});
+}
+
+#[test]
+fn create_package_validates_input() {
+	build_and_execute(|| {
+		assert_noop!(
+			create_package_as(CLIENT, vec![b'a'; 257], b"Books".to_vec()),
+			Error::<Test>::InvalidThreeWordAddress
+		);
+		assert_noop!(
+			create_package_as(CLIENT, b"filled.count.soap".to_vec(), vec![b'a'; 65]),
+			Error::<Test>::InvalidDescription
+		);
+		assert_ok!(create_package_as(CLIENT, vec![b'a'; 256], vec![b'a'; 64]));
+	});
+}
+
+#[test]
+fn create_package_requires_ernest_deposit() {
+	build_and_execute(|| {
+		assert_noop!(
+			create_package_as(3, b"filled.count.soap".to_vec(), b"Books".to_vec()),
+			Error::<Test>::InsufficientFunds
+		);
+	});
+}
+
+#[test]
+fn collect_package_requires_existing_package() {
+	build_and_execute(|| {
+		assert_noop!(
+			PackageModule::collect_package(&CLIENT, &PACKAGE_ID, &CARRIER),
+			Error::<Test>::PackageDoesNotExist
+		);
+	});
+}
+
+#[test]
+fn carrier_only_actions_require_assigned_carrier() {
+	new_test_ext().execute_with(|| {
+		Packages::<Test>::insert(CLIENT, PACKAGE_ID, package_with_status(PackageStatus::InTransit));
+
+		assert_noop!(
+			Pallet::<Test>::hold_package(&CLIENT, &PACKAGE_ID, &3),
+			Error::<Test>::InvalidCarrier
+		);
+		assert_noop!(
+			Pallet::<Test>::return_package(&CLIENT, &PACKAGE_ID, &3),
+			Error::<Test>::InvalidCarrier
+		);
+		assert_noop!(
+			Pallet::<Test>::report_package_lost(&CLIENT, &PACKAGE_ID, &3),
+			Error::<Test>::InvalidCarrier
+		);
+		assert_ok!(Pallet::<Test>::hold_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+	});
+}
+
+#[test]
+fn metadata_reserves_a_deposit_per_byte() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		create_package(PACKAGE_ID);
+		assert_eq!(Balances::reserved_balance(CLIENT), 10);
+
+		assert_ok!(PackageModule::set_metadata(
+			&CLIENT,
+			&PACKAGE_ID,
+			b"po".to_vec(),
+			b"PO-1234".to_vec()
+		));
+		assert_eq!(MetadataDeposits::<Test>::get(CLIENT, PACKAGE_ID), 9);
+		assert_eq!(Balances::reserved_balance(CLIENT), 19);
+		System::assert_last_event(
+			crate::Event::MetadataSet {
+				client: CLIENT,
+				package: PACKAGE_ID,
+				key: b"po".to_vec().try_into().unwrap(),
+			}
+			.into(),
+		);
+
+		// Replacing a value only reserves or releases the difference
+		assert_ok!(PackageModule::set_metadata(
+			&CLIENT,
+			&PACKAGE_ID,
+			b"po".to_vec(),
+			b"1".to_vec()
+		));
+		assert_eq!(Balances::reserved_balance(CLIENT), 13);
+
+		assert_ok!(PackageModule::clear_metadata(&CLIENT, &PACKAGE_ID, b"po".to_vec()));
+		assert_eq!(Balances::reserved_balance(CLIENT), 10);
+		assert!(!PackageMetadata::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert!(!MetadataDeposits::<Test>::contains_key(CLIENT, PACKAGE_ID));
+	});
+}
+
+#[test]
+fn metadata_is_bounded() {
+	build_and_execute(|| {
+		assert_noop!(
+			PackageModule::set_metadata(&CLIENT, &PACKAGE_ID, b"po".to_vec(), b"1".to_vec()),
+			Error::<Test>::PackageDoesNotExist
+		);
+		create_package(PACKAGE_ID);
+
+		assert_noop!(
+			PackageModule::set_metadata(&CLIENT, &PACKAGE_ID, vec![b'k'; 9], b"1".to_vec()),
+			Error::<Test>::InvalidMetadata
+		);
+		assert_noop!(
+			PackageModule::set_metadata(&CLIENT, &PACKAGE_ID, b"po".to_vec(), vec![b'v'; 17]),
+			Error::<Test>::InvalidMetadata
+		);
+
+		assert_ok!(PackageModule::set_metadata(&CLIENT, &PACKAGE_ID, b"a".to_vec(), b"1".to_vec()));
+		assert_ok!(PackageModule::set_metadata(&CLIENT, &PACKAGE_ID, b"b".to_vec(), b"2".to_vec()));
+		assert_noop!(
+			PackageModule::set_metadata(&CLIENT, &PACKAGE_ID, b"c".to_vec(), b"3".to_vec()),
+			Error::<Test>::TooManyMetadataEntries
+		);
+
+		// Existing entries can still be replaced once full
+		assert_ok!(PackageModule::set_metadata(&CLIENT, &PACKAGE_ID, b"b".to_vec(), b"4".to_vec()));
+		assert_noop!(
+			PackageModule::clear_metadata(&CLIENT, &PACKAGE_ID, b"c".to_vec()),
+			Error::<Test>::MetadataDoesNotExist
+		);
+	});
+}
+
+#[test]
+fn packages_can_be_found_by_external_reference() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		create_package(PACKAGE_ID);
+		create_package([2; 32]);
+
+		assert_ok!(PackageModule::set_external_reference(
+			&CLIENT,
+			&PACKAGE_ID,
+			Some(b"SO-1".to_vec())
+		));
+		assert_eq!(PackageModule::package_by_reference(&CLIENT, b"SO-1"), Some(PACKAGE_ID));
+		assert_eq!(PackageModule::package_by_reference(&CARRIER, b"SO-1"), None);
+		assert_eq!(Balances::reserved_balance(CLIENT), 24);
+
+		// References are unique among a client's packages
+		assert_noop!(
+			PackageModule::set_external_reference(&CLIENT, &[2; 32], Some(b"SO-1".to_vec())),
+			Error::<Test>::ReferenceInUse
+		);
+		assert_noop!(
+			PackageModule::set_external_reference(&CLIENT, &[2; 32], Some(vec![b'r'; 17])),
+			Error::<Test>::InvalidReference
+		);
+
+		// Changing a reference frees up the old one
+		assert_ok!(PackageModule::set_external_reference(
+			&CLIENT,
+			&PACKAGE_ID,
+			Some(b"SO-2".to_vec())
+		));
+		assert_eq!(PackageModule::package_by_reference(&CLIENT, b"SO-1"), None);
+		assert_ok!(PackageModule::set_external_reference(
+			&CLIENT,
+			&[2; 32],
+			Some(b"SO-1".to_vec())
+		));
+		assert_eq!(PackageModule::package_by_reference(&CLIENT, b"SO-1"), Some([2; 32]));
+
+		assert_ok!(PackageModule::set_external_reference(&CLIENT, &PACKAGE_ID, None));
+		assert_eq!(PackageModule::package_by_reference(&CLIENT, b"SO-2"), None);
+		assert_eq!(Balances::reserved_balance(CLIENT), 24);
+		System::assert_last_event(
+			crate::Event::ExternalReferenceSet {
+				client: CLIENT,
+				package: PACKAGE_ID,
+				reference: None,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn pruning_removes_metadata_and_releases_its_deposit() {
+	build_and_execute(|| {
+		create_package(PACKAGE_ID);
+		assert_ok!(PackageModule::set_metadata(
+			&CLIENT,
+			&PACKAGE_ID,
+			b"po".to_vec(),
+			b"1".to_vec()
+		));
+		assert_ok!(PackageModule::set_external_reference(
+			&CLIENT,
+			&PACKAGE_ID,
+			Some(b"SO-1".to_vec())
+		));
+		assert_ok!(Pallet::<Test>::cancel_package(&CLIENT, &PACKAGE_ID));
+
+		assert_eq!(Pallet::<Test>::prune_next_concluded(11), Some((CLIENT, PACKAGE_ID)));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert!(!PackageMetadata::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert!(!ExternalReferences::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert_eq!(PackagesByReference::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn update_package_amends_addresses_and_manifest() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		create_package(PACKAGE_ID);
+
+		assert_ok!(PackageModule::update_package(
+			&CLIENT,
+			&PACKAGE_ID,
+			b"filled.count.soup".to_vec(),
+			b"index.home.raft".to_vec(),
+			manifest(b"Glassware".to_vec(), true).unwrap(),
+		));
+
+		let package = Packages::<Test>::get(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(package.pickup.into_inner(), b"filled.count.soup".to_vec());
+		assert_eq!(package.status, PackageStatus::New);
+		assert_eq!(
+			Manifests::<Test>::get(CLIENT, PACKAGE_ID),
+			Some(manifest(b"Glassware".to_vec(), true).unwrap())
+		);
+		System::assert_last_event(
+			crate::Event::PackageUpdated { client: CLIENT, package: PACKAGE_ID }.into(),
+		);
+
+		// Line items keep deciding the manifest's totals
+		assert_ok!(PackageModule::set_line_items(
+			&CLIENT,
+			&PACKAGE_ID,
+			vec![line_item(b"MUG", 4, 25)]
+		));
+		assert_ok!(PackageModule::update_package(
+			&CLIENT,
+			&PACKAGE_ID,
+			b"filled.count.soap".to_vec(),
+			b"index.home.raft".to_vec(),
+			manifest(b"Mugs".to_vec(), false).unwrap(),
+		));
+		let manifest = Manifests::<Test>::get(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!((manifest.weight, manifest.declared_value), (8, 100));
+	});
+}
+
+#[test]
+fn update_package_requires_new_package() {
+	build_and_execute(|| {
+		assert_noop!(
+			PackageModule::update_package(
+				&CLIENT,
+				&PACKAGE_ID,
+				b"filled.count.soap".to_vec(),
+				b"index.home.raft".to_vec(),
+				manifest(b"Books".to_vec(), false).unwrap(),
+			),
+			Error::<Test>::PackageDoesNotExist
+		);
+
+		create_package(PACKAGE_ID);
+		assert_noop!(
+			PackageModule::update_package(
+				&CLIENT,
+				&PACKAGE_ID,
+				vec![b'a'; 1_000],
+				b"index.home.raft".to_vec(),
+				manifest(b"Books".to_vec(), false).unwrap(),
+			),
+			Error::<Test>::InvalidThreeWordAddress
+		);
+
+		assert_ok!(Pallet::<Test>::assign_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_noop!(
+			PackageModule::update_package(
+				&CLIENT,
+				&PACKAGE_ID,
+				b"filled.count.soup".to_vec(),
+				b"index.home.raft".to_vec(),
+				manifest(b"Books".to_vec(), false).unwrap(),
+			),
+			Error::<Test>::PackageNotNew
+		);
+	});
+}
+
+#[test]
+fn line_items_set_manifest_totals() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		create_package(PACKAGE_ID);
+		assert_ok!(PackageModule::set_line_items(
+			&CLIENT,
+			&PACKAGE_ID,
+			vec![line_item(b"MUG", 4, 25), line_item(b"PLATE", 2, 100)]
+		));
+
+		let manifest = Manifests::<Test>::get(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(manifest.weight, 12);
+		assert_eq!(manifest.declared_value, 300);
+		assert_eq!(PackageModule::line_items(&CLIENT, &PACKAGE_ID).len(), 2);
+		System::assert_last_event(
+			crate::Event::LineItemsSet { client: CLIENT, package: PACKAGE_ID, count: 2 }.into(),
+		);
+
+		// Clearing line items leaves the manifest as it was
+		assert_ok!(PackageModule::set_line_items(&CLIENT, &PACKAGE_ID, vec![]));
+		assert!(!PackageLineItems::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert_eq!(Manifests::<Test>::get(CLIENT, PACKAGE_ID), Some(manifest));
+	});
+}
+
+#[test]
+fn line_items_are_validated() {
+	build_and_execute(|| {
+		create_package(PACKAGE_ID);
+
+		assert!(matches!(
+			LineItem::<Test>::new((b"MUG".to_vec(), b"Item".to_vec(), 0, 2, 25)),
+			Err(Error::<Test>::InvalidLineItem)
+		));
+		assert!(matches!(
+			LineItem::<Test>::new((b"TOO-LONG-SKU".to_vec(), b"Item".to_vec(), 1, 2, 25)),
+			Err(Error::<Test>::InvalidLineItem)
+		));
+		assert_noop!(
+			PackageModule::set_line_items(
+				&CLIENT,
+				&PACKAGE_ID,
+				vec![
+					line_item(b"A", 1, 1),
+					line_item(b"B", 1, 1),
+					line_item(b"C", 1, 1),
+					line_item(b"D", 1, 1)
+				]
+			),
+			Error::<Test>::TooManyLineItems
+		);
+
+		// Contents are fixed once a carrier has been assigned
+		assert_ok!(Pallet::<Test>::assign_package(&CLIENT, &PACKAGE_ID, &CARRIER));
+		assert_noop!(
+			PackageModule::set_line_items(&CLIENT, &PACKAGE_ID, vec![line_item(b"MUG", 1, 25)]),
+			Error::<Test>::PackageNotNew
+		);
+	});
+}
+
+#[test]
+fn received_share_is_weighted_by_declared_value() {
+	build_and_execute(|| {
+		create_package(PACKAGE_ID);
+		assert_noop!(
+			PackageModule::confirm_received(&CLIENT, &PACKAGE_ID, vec![]),
+			Error::<Test>::NoLineItems
+		);
+		assert_ok!(PackageModule::set_line_items(
+			&CLIENT,
+			&PACKAGE_ID,
+			vec![line_item(b"MUG", 4, 25), line_item(b"PLATE", 2, 100)]
+		));
+		assert_eq!(PackageModule::missing_value(&CLIENT, &PACKAGE_ID), None);
+
+		assert_noop!(
+			PackageModule::confirm_received(&CLIENT, &PACKAGE_ID, vec![4]),
+			Error::<Test>::InvalidReceivedQuantities
+		);
+		assert_noop!(
+			PackageModule::confirm_received(&CLIENT, &PACKAGE_ID, vec![5, 2]),
+			Error::<Test>::InvalidReceivedQuantities
+		);
+
+		// Every mug but only one of the plates arrived
+		assert_eq!(
+			PackageModule::confirm_received(&CLIENT, &PACKAGE_ID, vec![4, 1]),
+			Ok(Perbill::from_rational(2u32, 3u32))
+		);
+		assert_eq!(
+			PackageReceived::<Test>::get(CLIENT, PACKAGE_ID).unwrap().into_inner(),
+			vec![4, 1]
+		);
+		assert_eq!(PackageModule::missing_value(&CLIENT, &PACKAGE_ID), Some(100));
+	});
+}
+
+#[test]
+fn received_share_falls_back_to_quantity_without_declared_values() {
+	build_and_execute(|| {
+		create_package(PACKAGE_ID);
+		assert_ok!(PackageModule::set_line_items(
+			&CLIENT,
+			&PACKAGE_ID,
+			vec![line_item(b"FLYER", 3, 0), line_item(b"POSTER", 1, 0)]
+		));
+
+		assert_eq!(
+			PackageModule::confirm_received(&CLIENT, &PACKAGE_ID, vec![3, 0]),
+			Ok(Perbill::from_percent(75))
+		);
+		assert_eq!(PackageModule::missing_value(&CLIENT, &PACKAGE_ID), Some(0));
+	});
+}
+
+#[test]
+fn pruning_removes_line_items() {
+	build_and_execute(|| {
+		create_package(PACKAGE_ID);
+		assert_ok!(PackageModule::set_line_items(
+			&CLIENT,
+			&PACKAGE_ID,
+			vec![line_item(b"MUG", 4, 25)]
+		));
+		assert_ok!(PackageModule::confirm_received(&CLIENT, &PACKAGE_ID, vec![3]));
+		assert_ok!(Pallet::<Test>::cancel_package(&CLIENT, &PACKAGE_ID));
+
+		assert_eq!(Pallet::<Test>::prune_next_concluded(11), Some((CLIENT, PACKAGE_ID)));
+		assert!(!PackageLineItems::<Test>::contains_key(CLIENT, PACKAGE_ID));
+		assert!(!PackageReceived::<Test>::contains_key(CLIENT, PACKAGE_ID));
+	});
+}
+
+#[test]
+fn migration_to_v1_adds_declared_value_to_manifests() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<PackageModule>();
+		let old = v1::OldManifest::<Test> {
+			description: b"Books".to_vec().try_into().unwrap(),
+			dimensions: Dimensions { length: 10, width: 20, height: 30 },
+			weight: 5,
+			hazardous_materials: true,
+			climate_controlled: false,
+		};
+		unhashed::put(&Manifests::<Test>::hashed_key_for(CLIENT, PACKAGE_ID), &old);
+
+		#[cfg(feature = "try-runtime")]
+		let state = v1::MigrateToV1::<Test>::pre_upgrade().unwrap();
+		v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(v1::MigrateToV1::<Test>::post_upgrade(state));
+
+		let manifest = Manifests::<Test>::get(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(manifest.description.to_vec(), b"Books".to_vec());
+		assert_eq!(manifest.dimensions, Dimensions { length: 10, width: 20, height: 30 });
+		assert_eq!(manifest.weight, 5);
+		assert!(manifest.hazardous_materials);
+		assert!(!manifest.climate_controlled);
+		assert_eq!(manifest.declared_value, 0);
+		assert_eq!(PackageModule::on_chain_storage_version(), 1);
+
+		// Once upgraded, the migration leaves manifests untouched
+		create_package([2; 32]);
+		v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Manifests::<Test>::get(CLIENT, [2; 32]).unwrap().declared_value, 500);
+	});
+}
+
+#[test]
+fn migration_to_v2_records_ernest_deposits_of_open_packages() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<PackageModule>();
+		Packages::<Test>::insert(CLIENT, PACKAGE_ID, package_with_status(PackageStatus::InTransit));
+		Packages::<Test>::insert(CLIENT, [2; 32], package_with_status(PackageStatus::Delivered));
+
+		#[cfg(feature = "try-runtime")]
+		let state = v2::MigrateToV2::<Test>::pre_upgrade().unwrap();
+		v2::MigrateToV2::<Test>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(v2::MigrateToV2::<Test>::post_upgrade(state));
+
+		assert_eq!(ErnestDeposits::<Test>::get(CLIENT, PACKAGE_ID), 10);
+		assert!(!ErnestDeposits::<Test>::contains_key(CLIENT, [2; 32]));
+		assert_eq!(PackageModule::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn migration_to_v3_leaves_chosen_ids_unindexed() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<PackageModule>();
+		Packages::<Test>::insert(CLIENT, PACKAGE_ID, package_with_status(PackageStatus::InTransit));
+		Packages::<Test>::insert(CLIENT, [2; 32], package_with_status(PackageStatus::Delivered));
+
+		#[cfg(feature = "try-runtime")]
+		let state = v3::MigrateToV3::<Test>::pre_upgrade().unwrap();
+		v3::MigrateToV3::<Test>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(v3::MigrateToV3::<Test>::post_upgrade(state));
+
+		assert_eq!(PackageClients::<Test>::get(PACKAGE_ID), None);
+		assert_eq!(PackageClients::<Test>::get([2; 32]), None);
+		assert_eq!(PackageModule::on_chain_storage_version(), 3);
+	});
+}
+
+#[test]
+fn migration_to_v4_rebuilds_package_indexes() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(3).put::<PackageModule>();
+
+		let insert = |package_id: PackageId, status, carrier| {
+			let mut package = package_with_status(status);
+			package.id = package_id;
+			package.carrier = carrier;
+			if status == PackageStatus::Delivered {
+				package.collected_on = Some(3);
+				package.delivered_on = Some(5);
+			}
+			Packages::<Test>::insert(CLIENT, package_id, package);
+			Manifests::<Test>::insert(
+				CLIENT,
+				package_id,
+				manifest(b"Books".to_vec(), false).unwrap(),
+			);
+		};
+		insert([1; 32], PackageStatus::New, None);
+		insert([2; 32], PackageStatus::Assigned, Some(CARRIER));
+		insert([3; 32], PackageStatus::Delivered, Some(CARRIER));
+		insert([4; 32], PackageStatus::Cancelled, None);
+
+		// Only the cancelled package made it into the queue, after the delivered package concluded
+		ConcludedPackages::<Test>::insert(5, (CLIENT, [4; 32], 7));
+		ConcludedHead::<Test>::put(5);
+		ConcludedTail::<Test>::put(6);
+
+		#[cfg(feature = "try-runtime")]
+		let state = v4::MigrateToV4::<Test>::pre_upgrade().unwrap();
+		v4::MigrateToV4::<Test>::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(v4::MigrateToV4::<Test>::post_upgrade(state));
+
+		assert_eq!(Pallet::<Test>::open_packages(0, 10), vec![(CLIENT, [1; 32])]);
+		assert_eq!(PackagesByStatus::<Test>::iter_keys().count(), 4);
+		assert_eq!(Pallet::<Test>::carrier_workload(&CARRIER), vec![(CLIENT, [2; 32])]);
+
+		// Queued by the block each package concluded in
+		assert_eq!((ConcludedHead::<Test>::get(), ConcludedTail::<Test>::get()), (0, 2));
+		assert_eq!(ConcludedPackages::<Test>::get(0), Some((CLIENT, [3; 32], 5)));
+		assert_eq!(ConcludedPackages::<Test>::get(1), Some((CLIENT, [4; 32], 7)));
+
+		assert_eq!(
+			Pallet::<Test>::timeline(&CLIENT, &[3; 32])
+				.into_iter()
+				.map(|entry| (entry.status, entry.block, entry.actor))
+				.collect::<Vec<_>>(),
+			vec![
+				(PackageStatus::New, 0, CLIENT),
+				(PackageStatus::InTransit, 3, CARRIER),
+				(PackageStatus::Delivered, 5, CLIENT),
+			]
+		);
+
+		assert_eq!(PackageModule::on_chain_storage_version(), 4);
+		assert_ok!(PackageModule::do_try_state());
+	});
+}
+
+#[test]
+fn pruning_releases_unclaimed_ernest_deposit() {
+	build_and_execute(|| {
+		create_package(PACKAGE_ID);
+		assert_ok!(Pallet::<Test>::cancel_package(&CLIENT, &PACKAGE_ID));
+		assert_eq!(Balances::reserved_balance(CLIENT), 10);
+
+		assert_eq!(Pallet::<Test>::prune_next_concluded(11), Some((CLIENT, PACKAGE_ID)));
+		assert_eq!(Balances::reserved_balance(CLIENT), 0);
+		assert!(!ErnestDeposits::<Test>::contains_key(CLIENT, PACKAGE_ID));
+	});
+}
+
+#[test]
+fn genesis_packages_are_open_and_reserve_deposits() {
+	let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig {
+		balances: pallet_balances::GenesisConfig { balances: vec![(CLIENT, 1_000)] },
+		package_module: crate::GenesisConfig {
+			packages: vec![(
+				CLIENT,
+				PACKAGE_ID,
+				b"filled.count.soap".to_vec(),
+				b"index.home.raft".to_vec(),
+				b"Books".to_vec(),
+				(10, 20, 30),
+				5,
+				500,
+			)],
+		},
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap()
+	.into();
+
+	ext.execute_with(|| {
+		assert_eq!(Pallet::<Test>::open_packages(0, 10), vec![(CLIENT, PACKAGE_ID)]);
+		assert_eq!(Balances::reserved_balance(CLIENT), 10);
+
+		let manifest = Manifests::<Test>::get(CLIENT, PACKAGE_ID).unwrap();
+		assert_eq!(manifest.dimensions, Dimensions { length: 10, width: 20, height: 30 });
+		assert_eq!(manifest.declared_value, 500);
+
+		// New chains start at the current storage version and need no migrations
+		assert_eq!(
+			PackageModule::on_chain_storage_version(),
+			PackageModule::current_storage_version()
+		);
+		assert_ok!(PackageModule::do_try_state());
+	});
+}
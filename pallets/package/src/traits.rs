@@ -0,0 +1,201 @@
+//! Interfaces through which other pallets work with packages.
+//!
+//! This pallet has no calls of its own. Packages are only changed through [`PackageMutate`],
+//! by a pallet which keeps any funds tied to a package consistent with its status.
+
+use crate::{types::*, BalanceOf, Config};
+use frame_support::{
+	dispatch::{DispatchError, DispatchResult},
+	sp_runtime::Perbill,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_std::vec::Vec;
+
+/// Read access to packages and their manifests
+pub trait PackageInspect<T: Config> {
+	/// Package a client created under `package_id`
+	fn package(client: &T::AccountId, package_id: &PackageId) -> Option<Package<T>>;
+
+	/// Declared contents of a package
+	fn manifest(client: &T::AccountId, package_id: &PackageId) -> Option<Manifest<T>>;
+
+	/// Client of the package with the derived `package_id`, whoever created it
+	fn client_of(package_id: &PackageId) -> Option<T::AccountId>;
+
+	/// Package with the derived `package_id` along with its client, for when only the ID is known
+	fn package_by_id(package_id: &PackageId) -> Option<(T::AccountId, Package<T>)>;
+
+	/// Packages a client has created and which have not been pruned yet,
+	/// skipping the first `offset` and returning at most `limit`
+	fn packages_of(client: &T::AccountId, offset: u32, limit: u32) -> Vec<(PackageId, Package<T>)>;
+
+	/// Metadata the client has attached to a package
+	fn metadata(client: &T::AccountId, package_id: &PackageId) -> Metadata<T>;
+
+	/// Package a client knows by `reference` in their own systems
+	fn package_by_reference(client: &T::AccountId, reference: &[u8]) -> Option<PackageId>;
+
+	/// Line items of a package shipping a mix of items
+	fn line_items(client: &T::AccountId, package_id: &PackageId) -> LineItems<T>;
+
+	/// Declared value of the line items which did not arrive,
+	/// or `None` until the client has confirmed what was received
+	fn missing_value(client: &T::AccountId, package_id: &PackageId) -> Option<BalanceOf<T>>;
+
+	/// Packages with the given status as (client, package id),
+	/// skipping the first `offset` and returning at most `limit`
+	fn packages_with_status(
+		status: PackageStatus,
+		offset: u32,
+		limit: u32,
+	) -> Vec<(T::AccountId, PackageId)>;
+
+	/// Packages assigned to a carrier which have not concluded yet
+	fn carrier_workload(carrier: &T::AccountId) -> Vec<(T::AccountId, PackageId)>;
+
+	/// Ensure a package exists and `action` is permitted from its current status
+	fn ensure_can_transition(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		action: PackageAction,
+	) -> Result<Package<T>, DispatchError>;
+
+	/// Ensure a package exists and has been assigned to `carrier`
+	fn ensure_carrier(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> Result<Package<T>, DispatchError>;
+
+	/// The oldest concluded package, if its retention period has passed
+	fn next_prunable(now: BlockNumberFor<T>) -> Option<(T::AccountId, PackageId)>;
+}
+
+/// Changes to packages. The ernest deposit is reserved when a package is created
+/// and kept until the caller releases it, any other funds are up to the caller.
+pub trait PackageMutate<T: Config>: PackageInspect<T> {
+	/// Create a package waiting for a carrier, reserving the ernest deposit from its client,
+	/// and return its ID. An ID chosen by the client only has to be unique among their own
+	/// packages. Without one, an ID unique across all clients is derived for the package.
+	fn create_package(
+		client: &T::AccountId,
+		package_id: Option<PackageId>,
+		pickup: Vec<u8>,
+		destination: Vec<u8>,
+		manifest: Manifest<T>,
+	) -> Result<PackageId, DispatchError>;
+
+	/// Replace the addresses and manifest of a package which is still waiting for a carrier.
+	/// The manifest's weight and declared value are kept at the totals of any line items.
+	fn update_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		pickup: Vec<u8>,
+		destination: Vec<u8>,
+		manifest: Manifest<T>,
+	) -> DispatchResult;
+
+	/// Assign a new package to a carrier
+	fn assign_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Collect a package on behalf of its assigned carrier
+	fn collect_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Put a package in transit on hold on behalf of its assigned carrier
+	fn hold_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Release a package which is on hold or disputed
+	fn release_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		actor: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Dispute a package on behalf of its client
+	fn dispute_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult;
+
+	/// Mark a package as delivered on behalf of its client
+	fn deliver_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult;
+
+	/// Return a package to its client on behalf of its assigned carrier
+	fn return_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Report a package lost on behalf of its assigned carrier
+	fn report_package_lost(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		carrier: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Cancel a new package on behalf of its client
+	fn cancel_package(client: &T::AccountId, package_id: &PackageId) -> DispatchResult;
+
+	/// Expire a package which has waited too long for a carrier
+	fn expire_package(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		actor: &T::AccountId,
+	) -> DispatchResult;
+
+	/// Return the ernest deposit reserved when a package was created to its client
+	fn release_ernest_deposit(client: &T::AccountId, package_id: &PackageId);
+
+	/// Replace the line items of a new package, updating its manifest's weight and declared
+	/// value to their totals
+	fn set_line_items(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		line_items: Vec<LineItem<T>>,
+	) -> DispatchResult;
+
+	/// Record the quantity of each line item received, returning the share of the declared
+	/// value which arrived
+	fn confirm_received(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		received: Vec<u32>,
+	) -> Result<Perbill, DispatchError>;
+
+	/// Attach a metadata entry to a package, replacing any value under the same key and
+	/// reserving a deposit for every byte stored
+	fn set_metadata(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		key: Vec<u8>,
+		value: Vec<u8>,
+	) -> DispatchResult;
+
+	/// Remove a metadata entry from a package, releasing its deposit
+	fn clear_metadata(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		key: Vec<u8>,
+	) -> DispatchResult;
+
+	/// Set or remove the reference a client's own systems know a package by
+	fn set_external_reference(
+		client: &T::AccountId,
+		package_id: &PackageId,
+		reference: Option<Vec<u8>>,
+	) -> DispatchResult;
+
+	/// Remove the oldest concluded package, along with its manifest, timeline and metadata,
+	/// if its retention period has passed
+	fn prune_next_concluded(now: BlockNumberFor<T>) -> Option<(T::AccountId, PackageId)>;
+}
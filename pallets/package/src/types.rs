@@ -1,22 +1,45 @@
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::{sp_runtime::RuntimeDebug, traits::ConstU32, BoundedVec};
+use frame_support::{
+	ensure,
+	sp_runtime::{traits::Saturating, RuntimeDebug},
+	traits::ConstU32,
+	BoundedBTreeMap, BoundedVec,
+};
 use frame_system::{self as system, pallet_prelude::BlockNumberFor, Config};
 use scale_info::TypeInfo;
+use sp_std::vec::Vec;
 
-use crate::pallet;
+use crate::{pallet, BalanceOf};
 
-pub type PackageId = [u8; 32];
+pub use logistics_primitives::{
+	PackageAction, PackageId, PackageStatus, TimelineEntry, PACKAGE_TRANSITIONS,
+};
 
 pub type ThreeWordAddress = BoundedVec<u8, ConstU32<256>>;
 
-#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
-pub enum PackageStatus {
-	New,
-	Assigned,
-	InTransit,
-	Delivered,
-	Cancelled,
-}
+pub type MetadataKey<T> = BoundedVec<u8, <T as pallet::Config>::MaxMetadataKeyLength>;
+pub type MetadataValue<T> = BoundedVec<u8, <T as pallet::Config>::MaxMetadataValueLength>;
+
+/// Key/value tags a client attaches to a package, such as a purchase order or cost centre
+pub type Metadata<T> =
+	BoundedBTreeMap<MetadataKey<T>, MetadataValue<T>, <T as pallet::Config>::MaxMetadataEntries>;
+
+/// Reference a client's own systems know a package by, such as an ERP order number
+pub type ExternalReference<T> = BoundedVec<u8, <T as pallet::Config>::MaxReferenceLength>;
+
+/// Package created at genesis as
+/// (client, package id, pickup, destination, description, (length, width, height), weight,
+/// declared value)
+pub type GenesisPackage<AccountId, Balance> =
+	(AccountId, PackageId, Vec<u8>, Vec<u8>, Vec<u8>, (u32, u32, u32), u32, Balance);
+
+/// Line item as (sku, description, quantity, unit weight, unit declared value)
+pub type LineItemParams<Balance> = (Vec<u8>, Vec<u8>, u32, u32, Balance);
+
+pub type LineItems<T> = BoundedVec<LineItem<T>, <T as pallet::Config>::MaxLineItems>;
+
+/// Quantity of each line item the client confirmed receiving, in line item order
+pub type ReceivedQuantities<T> = BoundedVec<u32, <T as pallet::Config>::MaxLineItems>;
 
 #[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
 pub struct Dimensions {
@@ -68,4 +91,65 @@ pub struct Manifest<T: Config + pallet::Config> {
 	pub weight: u32,
 	pub hazardous_materials: bool,
 	pub climate_controlled: bool,
+	/// Value of the contents as declared by the client
+	pub declared_value: BalanceOf<T>,
+}
+
+impl<T: Config + pallet::Config> Manifest<T> {
+	pub fn new(
+		description: Vec<u8>,
+		dimensions: Dimensions,
+		weight: u32,
+		hazardous_materials: bool,
+		climate_controlled: bool,
+		declared_value: BalanceOf<T>,
+	) -> Result<Self, pallet::Error<T>> {
+		Ok(Manifest {
+			description: description
+				.try_into()
+				.map_err(|_| pallet::Error::<T>::InvalidDescription)?,
+			dimensions,
+			weight,
+			hazardous_materials,
+			climate_controlled,
+			declared_value,
+		})
+	}
+}
+
+/// One kind of item in a mixed shipment
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+pub struct LineItem<T: Config + pallet::Config> {
+	pub sku: BoundedVec<u8, <T>::MaxSkuLength>,
+	pub description: BoundedVec<u8, <T>::DescriptionMaxLength>,
+	pub quantity: u32,
+	pub unit_weight: u32,
+	/// Value of a single unit as declared by the client
+	pub unit_declared_value: BalanceOf<T>,
+}
+
+impl<T: Config + pallet::Config> LineItem<T> {
+	pub fn new(
+		(sku, description, quantity, unit_weight, unit_declared_value): LineItemParams<
+			BalanceOf<T>,
+		>,
+	) -> Result<Self, pallet::Error<T>> {
+		ensure!(quantity > 0, pallet::Error::<T>::InvalidLineItem);
+
+		Ok(LineItem {
+			sku: sku.try_into().map_err(|_| pallet::Error::<T>::InvalidLineItem)?,
+			description: description
+				.try_into()
+				.map_err(|_| pallet::Error::<T>::InvalidDescription)?,
+			quantity,
+			unit_weight,
+			unit_declared_value,
+		})
+	}
+
+	/// Declared value of `quantity` units
+	pub fn value_of(&self, quantity: u32) -> BalanceOf<T> {
+		self.unit_declared_value.saturating_mul(quantity.into())
+	}
 }
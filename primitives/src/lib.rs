@@ -0,0 +1,169 @@
+//! Types shared by the logistics pallets, the runtime and the node.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod runtime_api;
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::AtLeast32BitUnsigned, RuntimeDebug};
+
+/// Most items the paginated runtime API methods return in a single call
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// Identifies a package. IDs are unique per client, so a package is looked up by both its
+/// client and its ID
+pub type PackageId = [u8; 32];
+
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum PackageStatus {
+	New,
+	Assigned,
+	InTransit,
+	Delivered,
+	Cancelled,
+	OnHold,
+	Disputed,
+	Returned,
+	Lost,
+	Expired,
+}
+
+impl PackageStatus {
+	pub const ALL: [PackageStatus; 10] = [
+		PackageStatus::New,
+		PackageStatus::Assigned,
+		PackageStatus::InTransit,
+		PackageStatus::Delivered,
+		PackageStatus::Cancelled,
+		PackageStatus::OnHold,
+		PackageStatus::Disputed,
+		PackageStatus::Returned,
+		PackageStatus::Lost,
+		PackageStatus::Expired,
+	];
+
+	/// Status reached by performing `action`, or `None` if the transition is not permitted
+	pub fn next(&self, action: PackageAction) -> Option<PackageStatus> {
+		PACKAGE_TRANSITIONS
+			.iter()
+			.find(|(from, on, _)| from == self && *on == action)
+			.map(|(_, _, to)| *to)
+	}
+
+	/// Package has reached a final status and no further actions are permitted
+	pub fn is_concluded(&self) -> bool {
+		PackageAction::ALL.iter().all(|action| self.next(*action).is_none())
+	}
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum PackageAction {
+	Assign,
+	Collect,
+	Hold,
+	Release,
+	Dispute,
+	Deliver,
+	Return,
+	ReportLost,
+	Cancel,
+	Expire,
+}
+
+impl PackageAction {
+	pub const ALL: [PackageAction; 10] = [
+		PackageAction::Assign,
+		PackageAction::Collect,
+		PackageAction::Hold,
+		PackageAction::Release,
+		PackageAction::Dispute,
+		PackageAction::Deliver,
+		PackageAction::Return,
+		PackageAction::ReportLost,
+		PackageAction::Cancel,
+		PackageAction::Expire,
+	];
+}
+
+/// Every permitted package transition as (from, action, to).
+/// Any combination not listed here is rejected.
+pub const PACKAGE_TRANSITIONS: &[(PackageStatus, PackageAction, PackageStatus)] = &[
+	(PackageStatus::New, PackageAction::Assign, PackageStatus::Assigned),
+	(PackageStatus::New, PackageAction::Cancel, PackageStatus::Cancelled),
+	(PackageStatus::New, PackageAction::Expire, PackageStatus::Expired),
+	(PackageStatus::Assigned, PackageAction::Collect, PackageStatus::InTransit),
+	(PackageStatus::InTransit, PackageAction::Hold, PackageStatus::OnHold),
+	(PackageStatus::InTransit, PackageAction::Dispute, PackageStatus::Disputed),
+	(PackageStatus::InTransit, PackageAction::Deliver, PackageStatus::Delivered),
+	(PackageStatus::InTransit, PackageAction::Return, PackageStatus::Returned),
+	(PackageStatus::InTransit, PackageAction::ReportLost, PackageStatus::Lost),
+	(PackageStatus::OnHold, PackageAction::Release, PackageStatus::InTransit),
+	(PackageStatus::OnHold, PackageAction::Dispute, PackageStatus::Disputed),
+	(PackageStatus::OnHold, PackageAction::Return, PackageStatus::Returned),
+	(PackageStatus::OnHold, PackageAction::ReportLost, PackageStatus::Lost),
+	(PackageStatus::Disputed, PackageAction::Release, PackageStatus::InTransit),
+	(PackageStatus::Disputed, PackageAction::Deliver, PackageStatus::Delivered),
+	(PackageStatus::Disputed, PackageAction::Return, PackageStatus::Returned),
+	(PackageStatus::Disputed, PackageAction::ReportLost, PackageStatus::Lost),
+];
+
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct TimelineEntry<AccountId, BlockNumber> {
+	/// Status the package moved into
+	pub status: PackageStatus,
+	/// Block in which the status changed
+	pub block: BlockNumber,
+	/// Milliseconds since the unix epoch at which the status changed
+	pub timestamp: u64,
+	/// Account which caused the status change
+	pub actor: AccountId,
+}
+
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum ProposalStatus {
+	Proposed,
+	Accepted,
+	Rejected,
+	Cancelled,
+	/// Package was amended after the proposal was made, so its carrier must confirm it
+	/// before it can be accepted
+	Stale,
+}
+
+impl ProposalStatus {
+	/// Proposal still holds its carrier's deposit and may yet be accepted or rejected
+	pub fn is_open(&self) -> bool {
+		matches!(self, ProposalStatus::Proposed | ProposalStatus::Stale)
+	}
+}
+
+/// Fee a carrier asks for delivering a package
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct FeeTerms<Balance> {
+	/// Paid when the package is delivered within the first penalty period
+	pub maximum_fee_amount: Balance,
+	/// Paid however late the package is delivered
+	pub minimum_fee_amount: Balance,
+	/// Number of blocks after collection before each penalty is deducted.
+	/// Zero means the carrier is never penalised.
+	pub penalty_period: u32,
+	/// Deducted from the maximum fee for every penalty period passed
+	pub penalty_amount: Balance,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> FeeTerms<Balance> {
+	/// Minimum fee does not exceed the maximum fee
+	pub fn is_valid(&self) -> bool {
+		self.minimum_fee_amount <= self.maximum_fee_amount
+	}
+
+	/// Fee owed for a delivery which took `blocks_taken` blocks from collection
+	pub fn final_fee(&self, blocks_taken: u32) -> Balance {
+		let periods = blocks_taken.checked_div(self.penalty_period).unwrap_or_default();
+		let penalty = self.penalty_amount.saturating_mul(periods.into());
+
+		// The carrier is always paid at least the minimum fee
+		self.maximum_fee_amount.saturating_sub(penalty).max(self.minimum_fee_amount)
+	}
+}
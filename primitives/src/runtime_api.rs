@@ -0,0 +1,63 @@
+//! Runtime API definitions for the logistics marketplace.
+
+use crate::{PackageId, TimelineEntry};
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait PackageApi<AccountId, BlockNumber>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Every recorded status change for a package, oldest first
+		fn timeline(client: AccountId, package_id: PackageId) -> Vec<TimelineEntry<AccountId, BlockNumber>>;
+	}
+
+	pub trait LogisticsApi<AccountId, BlockNumber, Balance, Package, Manifest, Proposal>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+		Package: Codec,
+		Manifest: Codec,
+		Proposal: Codec,
+	{
+		/// Packages a client has created and which have not been pruned yet,
+		/// skipping the first `offset` and returning at most `limit`
+		fn packages_of(client: AccountId, offset: u32, limit: u32) -> Vec<(PackageId, Package)>;
+
+		fn package(client: AccountId, package_id: PackageId) -> Option<Package>;
+
+		/// Package with the given derived ID along with its client, for when only the ID is known
+		fn package_by_id(package_id: PackageId) -> Option<(AccountId, Package)>;
+
+		/// Package a client knows by `reference` in their own systems
+		fn package_by_reference(client: AccountId, reference: Vec<u8>) -> Option<PackageId>;
+
+		/// Metadata the client has attached to a package, as (key, value)
+		fn metadata(client: AccountId, package_id: PackageId) -> Vec<(Vec<u8>, Vec<u8>)>;
+
+		fn manifest(client: AccountId, package_id: PackageId) -> Option<Manifest>;
+
+		/// Job board of packages waiting for a carrier, paged like `packages_of`
+		fn open_packages(offset: u32, limit: u32) -> Vec<Package>;
+
+		/// Packages assigned to a carrier which have not concluded yet, paged like `packages_of`
+		fn carrier_workload(carrier: AccountId, offset: u32, limit: u32) -> Vec<Package>;
+
+		/// Proposals made on a package whatever their status, paged like `packages_of`
+		fn proposals_for(client: AccountId, package_id: PackageId, offset: u32, limit: u32) -> Vec<Proposal>;
+
+		/// Proposals a carrier has made across all clients, paged like `packages_of`
+		fn proposals_by_carrier(carrier: AccountId, offset: u32, limit: u32) -> Vec<Proposal>;
+
+		/// Fee the carrier would be paid if the package were delivered at `delivered_at`
+		fn quote_final_fee(
+			client: AccountId,
+			package_id: PackageId,
+			carrier: AccountId,
+			delivered_at: BlockNumber,
+		) -> Option<Balance>;
+	}
+}
@@ -13,7 +13,8 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount,
+		NumberFor, One, Verify,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
@@ -27,8 +28,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
-		StorageInfo,
+		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains, Currency, KeyOwnerProofSystem,
+		OnUnbalanced, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -36,10 +37,12 @@ pub use frame_support::{
 		},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
+use frame_system::EnsureRoot;
 pub use pallet_balances::Call as BalancesCall;
+use pallet_logistics::types::MarketplaceParameters;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -55,6 +58,9 @@ pub use pallet_carrier;
 /// Import the logistics pallet.
 pub use pallet_logistics;
 
+/// Import the insurance pallet.
+pub use pallet_insurance;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -110,10 +116,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 112,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 8,
 	state_version: 1,
 };
 
@@ -160,7 +166,7 @@ parameter_types! {
 
 impl frame_system::Config for Runtime {
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = frame_support::traits::Everything;
+	type BaseCallFilter = pallet_logistics::PauseFilter<Runtime, SafeExits>;
 	/// The block type for the runtime.
 	type Block = Block;
 	/// Block & extrinsics weights: base values and limits.
@@ -276,8 +282,16 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
 	pub const DescriptionMaxLength: u32 = 256;
-	pub const ErnestDeposit: u128 = 10;
-	pub const MaxConcludedPackages: u32 = u32::MAX;
+	pub const MaxConcludedPackages: u32 = 64;
+	pub const MaxTimelineEntries: u32 = 32;
+	pub const MaxCarrierPackages: u32 = 128;
+	pub const MaxMetadataEntries: u32 = 16;
+	pub const MaxMetadataKeyLength: u32 = 32;
+	pub const MaxMetadataValueLength: u32 = 128;
+	pub const MaxReferenceLength: u32 = 64;
+	pub const MetadataDepositPerByte: Balance = 1;
+	pub const MaxLineItems: u32 = 32;
+	pub const MaxSkuLength: u32 = 32;
 }
 
 /// Configure the pallet-package in pallets/package.
@@ -286,13 +300,118 @@ impl pallet_package::Config for Runtime {
 	type Currency = Balances;
 	type Slashed = ();
 	type DescriptionMaxLength = DescriptionMaxLength;
-	type ErnestDeposit = ErnestDeposit;
+	type ErnestDeposit = pallet_logistics::ErnestDepositParameter<Runtime>;
 	type MaxConcludedPackages = MaxConcludedPackages;
+	type ConcludedRetentionPeriod = pallet_logistics::ConcludedRetentionPeriodParameter<Runtime>;
+	type PackageExpiry = pallet_logistics::PackageExpiryParameter<Runtime>;
+	type UnixTime = Timestamp;
+	type MaxTimelineEntries = MaxTimelineEntries;
+	type MaxCarrierPackages = MaxCarrierPackages;
+	type MaxMetadataEntries = MaxMetadataEntries;
+	type MaxMetadataKeyLength = MaxMetadataKeyLength;
+	type MaxMetadataValueLength = MaxMetadataValueLength;
+	type MaxReferenceLength = MaxReferenceLength;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type MaxLineItems = MaxLineItems;
+	type MaxSkuLength = MaxSkuLength;
+}
+
+parameter_types! {
+	pub const MaxProposalsPerPackage: u32 = 100;
 }
 
 /// Configure the pallet-carrier in pallets/carrier.
 impl pallet_carrier::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ProposalDeposit = pallet_logistics::ProposalDepositParameter<Runtime>;
+	type MaxProposalsPerPackage = MaxProposalsPerPackage;
+}
+
+parameter_types! {
+	pub const InsurancePalletId: PalletId = PalletId(*b"lgx/insr");
+	pub const ClaimPeriod: BlockNumber = 7 * DAYS;
+}
+
+/// Configure the pallet-insurance in pallets/insurance.
+impl pallet_insurance::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type PalletId = InsurancePalletId;
+	type BaseRate = pallet_logistics::InsuranceBaseRateParameter<Runtime>;
+	type HazardousMaterialsSurcharge =
+		pallet_logistics::HazardousMaterialsSurchargeParameter<Runtime>;
+	type ClimateControlledSurcharge =
+		pallet_logistics::ClimateControlledSurchargeParameter<Runtime>;
+	type ClaimPeriod = ClaimPeriod;
+	type ClaimOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = pallet_insurance::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	/// Marketplace parameters at genesis, adjustable afterwards by `ParametersOrigin`
+	pub const DefaultMarketplaceParameters: MarketplaceParameters<Balance, BlockNumber> =
+		MarketplaceParameters {
+			ernest_deposit: 10,
+			proposal_deposit: 5,
+			concluded_retention_period: 14 * DAYS,
+			package_expiry: 30 * DAYS,
+			insurance_base_rate: Permill::from_percent(1),
+			hazardous_materials_surcharge: Permill::from_percent(2),
+			climate_controlled_surcharge: Permill::from_parts(5_000),
+			commission_rate: Permill::from_percent(2),
+		};
+	pub const MinimumMarketplaceParameters: MarketplaceParameters<Balance, BlockNumber> =
+		MarketplaceParameters {
+			ernest_deposit: 1,
+			proposal_deposit: 1,
+			// Packages, and their cover, are kept until the claim period is over
+			concluded_retention_period: 7 * DAYS,
+			package_expiry: DAYS,
+			insurance_base_rate: Permill::zero(),
+			hazardous_materials_surcharge: Permill::zero(),
+			climate_controlled_surcharge: Permill::zero(),
+			commission_rate: Permill::zero(),
+		};
+	pub const MaximumMarketplaceParameters: MarketplaceParameters<Balance, BlockNumber> =
+		MarketplaceParameters {
+			ernest_deposit: 10_000,
+			proposal_deposit: 10_000,
+			concluded_retention_period: 90 * DAYS,
+			package_expiry: 180 * DAYS,
+			insurance_base_rate: Permill::from_percent(10),
+			hazardous_materials_surcharge: Permill::from_percent(10),
+			climate_controlled_surcharge: Permill::from_percent(10),
+			commission_rate: Permill::from_percent(10),
+		};
+}
+
+parameter_types! {
+	pub const MarketplacePalletId: PalletId = PalletId(*b"lgx/mrkt");
+}
+
+/// Pays the commission kept on delivered packages into the marketplace's account.
+/// Commission too small to keep that account alive is burnt instead.
+pub struct MarketplaceCommission;
+
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Runtime>> for MarketplaceCommission {
+	fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Runtime>) {
+		Balances::resolve_creating(&MarketplacePalletId::get().into_account_truncating(), amount);
+	}
+}
+
+/// Calls which stay available while the marketplace is paused,
+/// so clients and carriers can always get their deposits back.
+pub struct SafeExits;
+
+impl Contains<RuntimeCall> for SafeExits {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::LogisticsModule(pallet_logistics::Call::package_cancel { .. }) |
+				RuntimeCall::LogisticsModule(pallet_logistics::Call::proposal_withdraw { .. })
+		)
+	}
 }
 
 /// Configure the pallet-logistics in pallets/logistics.
@@ -300,8 +419,15 @@ impl pallet_logistics::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type Slashed = ();
-	type DescriptionMaxLength = DescriptionMaxLength;
-	type ErnestDeposit = ErnestDeposit;
+	type Commission = MarketplaceCommission;
+	type Packages = PackageModule;
+	type Proposals = CarrierModule;
+	type ParametersOrigin = EnsureRoot<AccountId>;
+	type DefaultParameters = DefaultMarketplaceParameters;
+	type MinimumParameters = MinimumMarketplaceParameters;
+	type MaximumParameters = MaximumMarketplaceParameters;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = pallet_logistics::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -318,6 +444,7 @@ construct_runtime!(
 		PackageModule: pallet_package,
 		CarrierModule: pallet_carrier,
 		LogisticsModule: pallet_logistics,
+		InsuranceModule: pallet_insurance,
 	}
 );
 
@@ -344,6 +471,16 @@ pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Storage migrations applied on runtime upgrade, in order.
+/// Each one only runs against the storage version it migrates from.
+pub type Migrations = (
+	pallet_package::migrations::v1::MigrateToV1<Runtime>,
+	pallet_carrier::migrations::v1::MigrateToV1<Runtime>,
+	pallet_package::migrations::v2::MigrateToV2<Runtime>,
+	pallet_package::migrations::v3::MigrateToV3<Runtime>,
+	pallet_package::migrations::v4::MigrateToV4<Runtime>,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -351,6 +488,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -365,6 +503,8 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_sudo, Sudo]
+		[pallet_logistics, LogisticsModule]
+		[pallet_insurance, InsuranceModule]
 	);
 }
 
@@ -536,6 +676,108 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl logistics_primitives::runtime_api::PackageApi<Block, AccountId, BlockNumber> for Runtime {
+		fn timeline(
+			client: AccountId,
+			package_id: logistics_primitives::PackageId,
+		) -> Vec<logistics_primitives::TimelineEntry<AccountId, BlockNumber>> {
+			PackageModule::timeline(&client, &package_id)
+		}
+	}
+
+	impl logistics_primitives::runtime_api::LogisticsApi<
+		Block,
+		AccountId,
+		BlockNumber,
+		Balance,
+		pallet_package::types::Package<Runtime>,
+		pallet_package::types::Manifest<Runtime>,
+		pallet_carrier::types::Proposal<Runtime>,
+	> for Runtime {
+		fn packages_of(
+			client: AccountId,
+			offset: u32,
+			limit: u32,
+		) -> Vec<(logistics_primitives::PackageId, pallet_package::types::Package<Runtime>)> {
+			LogisticsModule::packages_of(&client, offset, limit)
+		}
+
+		fn package(
+			client: AccountId,
+			package_id: logistics_primitives::PackageId,
+		) -> Option<pallet_package::types::Package<Runtime>> {
+			PackageModule::get_package(&client, &package_id)
+		}
+
+		fn package_by_id(
+			package_id: logistics_primitives::PackageId,
+		) -> Option<(AccountId, pallet_package::types::Package<Runtime>)> {
+			LogisticsModule::package_by_id(&package_id)
+		}
+
+		fn package_by_reference(
+			client: AccountId,
+			reference: Vec<u8>,
+		) -> Option<logistics_primitives::PackageId> {
+			LogisticsModule::package_by_reference(&client, &reference)
+		}
+
+		fn metadata(
+			client: AccountId,
+			package_id: logistics_primitives::PackageId,
+		) -> Vec<(Vec<u8>, Vec<u8>)> {
+			LogisticsModule::metadata(&client, &package_id)
+		}
+
+		fn manifest(
+			client: AccountId,
+			package_id: logistics_primitives::PackageId,
+		) -> Option<pallet_package::types::Manifest<Runtime>> {
+			pallet_package::Manifests::<Runtime>::get(&client, &package_id)
+		}
+
+		fn open_packages(
+			offset: u32,
+			limit: u32,
+		) -> Vec<pallet_package::types::Package<Runtime>> {
+			LogisticsModule::open_packages(offset, limit)
+		}
+
+		fn carrier_workload(
+			carrier: AccountId,
+			offset: u32,
+			limit: u32,
+		) -> Vec<pallet_package::types::Package<Runtime>> {
+			LogisticsModule::carrier_workload(&carrier, offset, limit)
+		}
+
+		fn proposals_for(
+			client: AccountId,
+			package_id: logistics_primitives::PackageId,
+			offset: u32,
+			limit: u32,
+		) -> Vec<pallet_carrier::types::Proposal<Runtime>> {
+			LogisticsModule::proposals_for(&client, &package_id, offset, limit)
+		}
+
+		fn proposals_by_carrier(
+			carrier: AccountId,
+			offset: u32,
+			limit: u32,
+		) -> Vec<pallet_carrier::types::Proposal<Runtime>> {
+			LogisticsModule::proposals_by_carrier(&carrier, offset, limit)
+		}
+
+		fn quote_final_fee(
+			client: AccountId,
+			package_id: logistics_primitives::PackageId,
+			carrier: AccountId,
+			delivered_at: BlockNumber,
+		) -> Option<Balance> {
+			LogisticsModule::quote_final_fee(&client, &package_id, &carrier, delivered_at)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
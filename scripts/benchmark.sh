@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+# Regenerates the weights of every benchmarked marketplace pallet.
+# Run from the repository root on reference hardware, then commit the updated weights.rs files.
+set -e
+
+PALLETS=(
+	"pallet_logistics:pallets/logistics/src/weights.rs"
+	"pallet_insurance:pallets/insurance/src/weights.rs"
+)
+
+echo "*** Building the node with runtime benchmarks enabled"
+cargo build --release --features runtime-benchmarks --package node-template
+
+for entry in "${PALLETS[@]}"; do
+	pallet="${entry%%:*}"
+	output="${entry#*:}"
+
+	echo "*** Benchmarking $pallet"
+	./target/release/node-template benchmark pallet \
+		--chain dev \
+		--pallet "$pallet" \
+		--extrinsic '*' \
+		--steps=50 \
+		--repeat=20 \
+		--wasm-execution=compiled \
+		--output "$output" \
+		--template .maintain/frame-weight-template.hbs
+done